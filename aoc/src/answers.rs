@@ -0,0 +1,148 @@
+use crate::toml::{parse_tables, quote};
+use aoc_common::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Known-good answer for one input. Entries are keyed by day, part and
+// input hash, so the same file name can't hide changed content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    // Input file, relative to the registry file.
+    pub input: PathBuf,
+    pub hash: String,
+    pub answer: String,
+}
+
+// Local registry of expected answers, saved as TOML:
+//
+//   [[answer]]
+//   day = 1
+//   part = 1
+//   input = "day-01/sonar-sweep/input.dat"
+//   hash = "fnv1a64:..."
+//   answer = "1400"
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Registry {
+    pub entries: Vec<Entry>,
+}
+
+impl Registry {
+    pub fn parse(text: &str) -> Result<Self> {
+        let keys = ["day", "part", "input", "hash", "answer"];
+        let entries = parse_tables(text, "answer", &keys)?
+            .into_iter()
+            .map(|table| {
+                Ok(Entry {
+                    day: table.integer("day")?,
+                    part: table.integer("part")?,
+                    input: PathBuf::from(table.string("input")?),
+                    hash: table.string("hash")?,
+                    answer: table.string("answer")?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Registry { entries })
+    }
+
+    // Missing file is an empty registry.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Registry::parse(&text).map_err(|e| e.with_path(path)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+            Err(e) => Err(Error::from(e).with_path(path)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_toml()).map_err(|e| Error::from(e).with_path(path))
+    }
+
+    // Add entry or replace answer of the entry with the same key.
+    pub fn insert(&mut self, new_entry: Entry) {
+        let same_key = |entry: &&mut Entry| {
+            entry.day == new_entry.day
+                && entry.part == new_entry.part
+                && entry.hash == new_entry.hash
+        };
+        match self.entries.iter_mut().find(same_key) {
+            Some(entry) => *entry = new_entry,
+            None => self.entries.push(new_entry),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::from("# Known-good answers checked by `aoc verify`.\n");
+        for entry in &self.entries {
+            toml.push_str(&format!(
+                "\n[[answer]]\nday = {}\npart = {}\ninput = {}\nhash = {}\nanswer = {}\n",
+                entry.day,
+                entry.part,
+                quote(&entry.input.to_string_lossy()),
+                quote(&entry.hash),
+                quote(&entry.answer),
+            ));
+        }
+        toml
+    }
+}
+
+// 64-bit FNV-1a hash of the input, written as `fnv1a64:<hex>`.
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("fnv1a64:{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_round_trip() {
+        let mut registry = Registry::default();
+        registry.insert(Entry {
+            day: 3,
+            part: 2,
+            input: PathBuf::from("day-03/part-two/data/example.dat"),
+            hash: input_hash("00100\n11110\n"),
+            answer: String::from("230"),
+        });
+        let parsed = Registry::parse(&registry.to_toml()).unwrap();
+        assert_eq!(parsed, registry);
+    }
+
+    #[test]
+    fn insert_replaces_same_key() {
+        let entry = Entry {
+            day: 1,
+            part: 1,
+            input: PathBuf::from("input.dat"),
+            hash: input_hash("199\n200\n"),
+            answer: String::from("1"),
+        };
+        let mut registry = Registry::default();
+        registry.insert(entry.clone());
+        registry.insert(Entry {
+            answer: String::from("2"),
+            ..entry.clone()
+        });
+        registry.insert(Entry {
+            hash: input_hash("199\n"),
+            ..entry
+        });
+        assert_eq!(registry.entries.len(), 2);
+        assert_eq!(registry.entries[0].answer, "2");
+    }
+
+    #[test]
+    fn malformed_registry() {
+        let error = Registry::parse("[[answer]]\nday = 1\npart = x\n").unwrap_err();
+        assert_eq!(error.to_string(), "3:8: expected integer, found \"x\"");
+
+        let error = Registry::parse("[[answer]]\nday = 1\npart = 1\n").unwrap_err();
+        assert_eq!(error.to_string(), "1:1: expected 'input' field, found \"\"");
+    }
+}
@@ -0,0 +1,192 @@
+use crate::toml::{parse_tables, quote};
+use aoc_common::{Error, Result, Solution};
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Statistics {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Statistics {
+    // Samples can't be empty.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        Statistics {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+// Timing of one phase of a day: parsing or solving one part.
+pub struct Phase {
+    pub name: &'static str,
+    pub statistics: Statistics,
+}
+
+pub struct DayBenchmark {
+    pub day: u8,
+    pub title: &'static str,
+    pub runs: usize,
+    pub phases: Vec<Phase>,
+}
+
+// Time parsing and both parts separately, every phase `runs` times.
+// Everything is run once before measuring, so errors are reported
+// instead of being measured.
+pub fn bench_day<S: Solution>(input: &str, runs: usize) -> Result<DayBenchmark> {
+    let parsed = S::parse(input)?;
+    S::part_one(&parsed)?;
+    S::part_two(&parsed)?;
+
+    let runs = runs.max(1);
+    let phases = vec![
+        Phase {
+            name: "parse",
+            statistics: measure(runs, || S::parse(input)),
+        },
+        Phase {
+            name: "part 1",
+            statistics: measure(runs, || S::part_one(&parsed)),
+        },
+        Phase {
+            name: "part 2",
+            statistics: measure(runs, || S::part_two(&parsed)),
+        },
+    ];
+    Ok(DayBenchmark {
+        day: S::DAY,
+        title: S::TITLE,
+        runs,
+        phases,
+    })
+}
+
+fn measure<T, F>(runs: usize, mut f: F) -> Statistics
+where
+    F: FnMut() -> T,
+{
+    let samples = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    Statistics::from_samples(samples)
+}
+
+// Median times saved from earlier run, used for comparison.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    pub entries: Vec<(u8, String, Duration)>,
+}
+
+impl Baseline {
+    pub fn parse(text: &str) -> Result<Self> {
+        let entries = parse_tables(text, "benchmark", &["day", "phase", "median_ns"])?
+            .into_iter()
+            .map(|table| {
+                Ok((
+                    table.integer("day")?,
+                    table.string("phase")?,
+                    Duration::from_nanos(table.integer("median_ns")?),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Baseline { entries })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| Error::from(e).with_path(path))?;
+        Baseline::parse(&text).map_err(|e| e.with_path(path))
+    }
+
+    pub fn from_benchmarks(benchmarks: &[DayBenchmark]) -> Self {
+        let entries = benchmarks
+            .iter()
+            .flat_map(|benchmark| {
+                benchmark.phases.iter().map(|phase| {
+                    (
+                        benchmark.day,
+                        phase.name.to_owned(),
+                        phase.statistics.median,
+                    )
+                })
+            })
+            .collect();
+        Baseline { entries }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut toml = String::from("# Median times saved by `aoc bench --save`.\n");
+        for (day, phase, median) in &self.entries {
+            toml.push_str(&format!(
+                "\n[[benchmark]]\nday = {}\nphase = {}\nmedian_ns = {}\n",
+                day,
+                quote(phase),
+                median.as_nanos()
+            ));
+        }
+        fs::write(path, toml).map_err(|e| Error::from(e).with_path(path))
+    }
+
+    pub fn median(&self, day: u8, phase: &str) -> Option<Duration> {
+        self.entries
+            .iter()
+            .find(|(entry_day, entry_phase, _)| *entry_day == day && entry_phase == phase)
+            .map(|(_, _, median)| *median)
+    }
+}
+
+// Relative change of `current` against `baseline` in percent.
+pub fn change(baseline: Duration, current: Duration) -> f64 {
+    let baseline = baseline.as_secs_f64();
+    if baseline == 0.0 {
+        return 0.0;
+    }
+    (current.as_secs_f64() - baseline) / baseline * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics() {
+        let samples = [5, 1, 4, 2, 3].map(Duration::from_millis).to_vec();
+        let statistics = Statistics::from_samples(samples);
+        assert_eq!(statistics.min, Duration::from_millis(1));
+        assert_eq!(statistics.median, Duration::from_millis(3));
+        assert_eq!(statistics.max, Duration::from_millis(5));
+
+        let samples = [4, 1, 2, 3].map(Duration::from_millis).to_vec();
+        let median = Statistics::from_samples(samples).median;
+        assert_eq!(median, Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let text = "[[benchmark]]\nday = 3\nphase = \"part 2\"\nmedian_ns = 1500\n";
+        let baseline = Baseline::parse(text).unwrap();
+        assert_eq!(
+            baseline.median(3, "part 2"),
+            Some(Duration::from_nanos(1500))
+        );
+        assert_eq!(baseline.median(3, "parse"), None);
+        let change = change(Duration::from_nanos(1500), Duration::from_nanos(1200));
+        assert!((change + 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,344 @@
+use answers::{input_hash, Entry, Registry};
+use aoc_common::{InputSource, Json, OutputFormat};
+use bench::{change, Baseline, DayBenchmark};
+use puzzles::{bundled_input, find_puzzle, workspace_root, Puzzle, DAYS, PUZZLES};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+mod answers;
+mod bench;
+mod puzzles;
+mod toml;
+
+const USAGE: &str = "Usage:
+    aoc run [--format text|json] <day> <part> [file_name | -]
+    aoc run [--format text|json] --all
+    aoc verify [--format text|json] [--answers <file_name>] [--record]
+    aoc bench [--format text|json] [--runs <n>] [--baseline <file_name>] [--save <file_name>] [day]...";
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).collect::<Vec<String>>();
+    let format = match OutputFormat::take_from_args(&mut args) {
+        Ok(format) => format,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let args = args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>();
+
+    let result = match args.as_slice() {
+        ["run", rest @ ..] => run(rest, format),
+        ["verify", rest @ ..] => verify(rest, format),
+        ["bench", rest @ ..] => bench(rest, format),
+        _ => Err(USAGE.to_owned()),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Returns `Ok(false)` when any puzzle failed.
+fn run(args: &[&str], format: OutputFormat) -> Result<bool, String> {
+    let runs = match args {
+        ["--all"] => PUZZLES
+            .iter()
+            .map(|puzzle| (puzzle, bundled_input(puzzle)))
+            .collect::<Vec<_>>(),
+        [day, part, rest @ ..] if rest.len() <= 1 => {
+            let puzzle = find_puzzle(day, part)?;
+            let input = match rest.first() {
+                Some(&"-") => InputSource::Stdin,
+                Some(file_name) => InputSource::File(PathBuf::from(file_name)),
+                None => bundled_input(puzzle),
+            };
+            vec![(puzzle, input)]
+        }
+        _ => return Err(USAGE.to_owned()),
+    };
+
+    let mut succeeded = true;
+    if format == OutputFormat::Text {
+        println!("{:>3}  {:>4}  {:<30}  Answer", "Day", "Part", "Puzzle");
+    }
+    for (puzzle, input) in runs {
+        let (answer, record) = (puzzle.solve)(&input, puzzle.part);
+        succeeded &= answer.is_ok();
+        if format == OutputFormat::Json {
+            println!("{}", record);
+            continue;
+        }
+        let answer = match answer {
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        println!(
+            "{:>3}  {:>4}  {:<30}  {}",
+            puzzle.day, puzzle.part, puzzle.name, answer
+        );
+    }
+    Ok(succeeded)
+}
+
+// Result of checking one registered answer.
+enum Status {
+    Pass,
+    // Solver returns different answer than the registered one.
+    Regression(String),
+    // Input file content doesn't match registered hash.
+    InputChanged,
+    Fail(String),
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Regression(_) => "regression",
+            Status::InputChanged => "input changed",
+            Status::Fail(_) => "fail",
+        }
+    }
+}
+
+// Run every solver against every registered input. With `--record`
+// answers for bundled inputs are saved in the registry instead.
+fn verify(args: &[&str], format: OutputFormat) -> Result<bool, String> {
+    let mut answers_path = workspace_root().join("answers.toml");
+    let mut record = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--answers" => {
+                let file_name = args.next().ok_or_else(|| USAGE.to_owned())?;
+                answers_path = PathBuf::from(file_name);
+            }
+            "--record" => record = true,
+            _ => return Err(USAGE.to_owned()),
+        }
+    }
+
+    let mut registry = Registry::load(&answers_path).map_err(|e| e.to_string())?;
+    if record {
+        return record_bundled_answers(&mut registry, &answers_path);
+    }
+    if registry.entries.is_empty() {
+        return Err(format!("no answers in {}", answers_path.display()));
+    }
+
+    // Inputs are relative to the registry file.
+    let base = answers_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut succeeded = true;
+    if format == OutputFormat::Text {
+        println!(
+            "{:>3}  {:>4}  {:<50}  {:<12}  {:<12}  Status",
+            "Day", "Part", "Input", "Expected", "Actual"
+        );
+    }
+    for entry in &registry.entries {
+        let status = check_entry(entry, base);
+        succeeded &= matches!(status, Status::Pass);
+
+        let actual = match &status {
+            Status::Pass => Some(entry.answer.clone()),
+            Status::Regression(actual) => Some(actual.clone()),
+            _ => None,
+        };
+        match format {
+            OutputFormat::Text => {
+                let status = match &status {
+                    Status::Fail(e) => format!("{}: {}", status.name(), e),
+                    status => status.name().to_owned(),
+                };
+                println!(
+                    "{:>3}  {:>4}  {:<50}  {:<12}  {:<12}  {}",
+                    entry.day,
+                    entry.part,
+                    entry.input.display(),
+                    entry.answer,
+                    actual.unwrap_or_else(|| String::from("-")),
+                    status
+                );
+            }
+            OutputFormat::Json => {
+                let error = match &status {
+                    Status::Fail(e) => Some(e.clone()),
+                    _ => None,
+                };
+                let record = Json::object()
+                    .field("day", entry.day)
+                    .field("part", entry.part)
+                    .field("input", entry.input.to_string_lossy().into_owned())
+                    .field("hash", entry.hash.as_str())
+                    .field("expected", entry.answer.as_str())
+                    .field("actual", actual)
+                    .field("status", status.name())
+                    .field("error", error);
+                println!("{}", record);
+            }
+        }
+    }
+    Ok(succeeded)
+}
+
+fn check_entry(entry: &Entry, base: &Path) -> Status {
+    let puzzle = match PUZZLES
+        .iter()
+        .find(|puzzle| puzzle.day == entry.day && puzzle.part == entry.part)
+    {
+        Some(puzzle) => puzzle,
+        None => return Status::Fail(String::from("no solution for this day and part")),
+    };
+    let source = InputSource::File(base.join(&entry.input));
+    let input = match source.read() {
+        Ok(input) => input,
+        Err(e) => return Status::Fail(e.to_string()),
+    };
+    if input_hash(&input) != entry.hash {
+        return Status::InputChanged;
+    }
+    match (puzzle.solve)(&source, puzzle.part) {
+        (Ok(answer), _) if answer.to_string() == entry.answer => Status::Pass,
+        (Ok(answer), _) => Status::Regression(answer.to_string()),
+        (Err(e), _) => Status::Fail(e.to_string()),
+    }
+}
+
+fn record_bundled_answers(registry: &mut Registry, answers_path: &Path) -> Result<bool, String> {
+    let base = answers_path.parent().unwrap_or_else(|| Path::new(""));
+    for puzzle in PUZZLES {
+        let entry = bundled_entry(puzzle, base)?;
+        println!(
+            "{:>3}  {:>4}  {:<30}  {}",
+            entry.day, entry.part, puzzle.name, entry.answer
+        );
+        registry.insert(entry);
+    }
+    registry.save(answers_path).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+fn bundled_entry(puzzle: &Puzzle, base: &Path) -> Result<Entry, String> {
+    let source = bundled_input(puzzle);
+    let input = source.read().map_err(|e| e.to_string())?;
+    let answer = (puzzle.solve)(&source, puzzle.part)
+        .0
+        .map_err(|e| e.to_string())?;
+    let input_path = match &source {
+        InputSource::File(path) => path.strip_prefix(base).unwrap_or(path).to_owned(),
+        InputSource::Stdin => PathBuf::from("-"),
+    };
+    Ok(Entry {
+        day: puzzle.day,
+        part: puzzle.part,
+        input: input_path,
+        hash: input_hash(&input),
+        answer: answer.to_string(),
+    })
+}
+
+// Time parsing and both parts of selected days (all by default) over
+// bundled inputs. Build with `--release` for meaningful numbers.
+fn bench(args: &[&str], format: OutputFormat) -> Result<bool, String> {
+    let mut runs = 10;
+    let mut baseline_path = None;
+    let mut save_path = None;
+    let mut days = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--runs" => {
+                let value = args.next().ok_or_else(|| USAGE.to_owned())?;
+                runs = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("incorrect number of runs: '{}'", value))?;
+            }
+            "--baseline" => baseline_path = Some(args.next().ok_or_else(|| USAGE.to_owned())?),
+            "--save" => save_path = Some(args.next().ok_or_else(|| USAGE.to_owned())?),
+            day => days.push(
+                day.parse::<u8>()
+                    .map_err(|_| format!("incorrect day: '{}'", day))?,
+            ),
+        }
+    }
+
+    let baseline = match baseline_path {
+        Some(path) => Baseline::load(Path::new(path)).map_err(|e| e.to_string())?,
+        None => Baseline::default(),
+    };
+
+    let mut benchmarks = Vec::new();
+    for day in DAYS
+        .iter()
+        .filter(|day| days.is_empty() || days.contains(&day.day))
+    {
+        let source = InputSource::File(workspace_root().join(day.input));
+        let input = source.read().map_err(|e| e.to_string())?;
+        let benchmark =
+            (day.bench)(&input, runs).map_err(|e| e.with_path(source.path()).to_string())?;
+        benchmarks.push(benchmark);
+    }
+    if benchmarks.is_empty() {
+        return Err(format!("no solution for days {:?}", days));
+    }
+
+    print_benchmarks(&benchmarks, &baseline, format);
+    if let Some(path) = save_path {
+        Baseline::from_benchmarks(&benchmarks)
+            .save(Path::new(path))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
+
+fn print_benchmarks(benchmarks: &[DayBenchmark], baseline: &Baseline, format: OutputFormat) {
+    let millis = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+    if format == OutputFormat::Text {
+        println!(
+            "{:>3}  {:<6}  {:>5}  {:>12}  {:>12}  {:>12}  {:>12}  {:>8}",
+            "Day", "Phase", "Runs", "Min", "Median", "Max", "Baseline", "Change"
+        );
+    }
+    for benchmark in benchmarks {
+        for phase in &benchmark.phases {
+            let statistics = &phase.statistics;
+            let base = baseline.median(benchmark.day, phase.name);
+            let change = base.map(|base| change(base, statistics.median));
+            match format {
+                OutputFormat::Text => println!(
+                    "{:>3}  {:<6}  {:>5}  {:>9.3} ms  {:>9.3} ms  {:>9.3} ms  {:>12}  {:>8}",
+                    benchmark.day,
+                    phase.name,
+                    benchmark.runs,
+                    millis(statistics.min),
+                    millis(statistics.median),
+                    millis(statistics.max),
+                    base.map_or_else(
+                        || String::from("-"),
+                        |base| format!("{:.3} ms", millis(base))
+                    ),
+                    change.map_or_else(|| String::from("-"), |change| format!("{:+.1}%", change)),
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    Json::object()
+                        .field("day", benchmark.day)
+                        .field("title", benchmark.title)
+                        .field("phase", phase.name)
+                        .field("runs", benchmark.runs)
+                        .field("min_ms", millis(statistics.min))
+                        .field("median_ms", millis(statistics.median))
+                        .field("max_ms", millis(statistics.max))
+                        .field("baseline_median_ms", base.map(millis))
+                        .field("change_percent", change)
+                ),
+            }
+        }
+    }
+}
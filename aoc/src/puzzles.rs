@@ -0,0 +1,162 @@
+use crate::bench::{bench_day, DayBenchmark};
+use aoc_common::{answer_record, error_record, Answer, InputSource, Json, Solution};
+use binary_diagnostic::BinaryDiagnostic;
+use dive::Dive;
+use giant_squid::GiantSquid;
+use hydrothermal_venture::HydrothermalVenture;
+use sonar_sweep::SonarSweep;
+use std::path::Path;
+use std::time::Instant;
+
+// Single puzzle (one part of one day) which can be run by `aoc`.
+pub struct Puzzle {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    // Bundled puzzle input, relative to the workspace root.
+    pub input: &'static str,
+    pub solve: fn(&InputSource, u8) -> (aoc_common::Result<Answer>, Json),
+}
+
+pub const PUZZLES: &[Puzzle] = &[
+    Puzzle {
+        day: 1,
+        part: 1,
+        name: "sonar-sweep",
+        input: "day-01/sonar-sweep/input.dat",
+        solve: solve::<SonarSweep>,
+    },
+    Puzzle {
+        day: 1,
+        part: 2,
+        name: "sonar-sweep-part-two",
+        input: "day-01/part-two/input.dat",
+        solve: solve::<SonarSweep>,
+    },
+    Puzzle {
+        day: 2,
+        part: 1,
+        name: "dive",
+        input: "day-02/dive/input.dat",
+        solve: solve::<Dive>,
+    },
+    Puzzle {
+        day: 2,
+        part: 2,
+        name: "dive-part-two",
+        input: "day-02/part-two/input.dat",
+        solve: solve::<Dive>,
+    },
+    Puzzle {
+        day: 3,
+        part: 1,
+        name: "binary-diagnostic",
+        input: "day-03/binary-diagnostic/data/puzzle_input.dat",
+        solve: solve::<BinaryDiagnostic>,
+    },
+    Puzzle {
+        day: 3,
+        part: 2,
+        name: "binary-diagnostic-part-two",
+        input: "day-03/part-two/data/input_puzzle.dat",
+        solve: solve::<BinaryDiagnostic>,
+    },
+    Puzzle {
+        day: 4,
+        part: 1,
+        name: "giant-squid",
+        input: "day-04/giant-squid/data/puzzle_input.dat",
+        solve: solve::<GiantSquid>,
+    },
+    Puzzle {
+        day: 4,
+        part: 2,
+        name: "giant-squid-part-two",
+        input: "day-04/part-two/data/puzzle_input.dat",
+        solve: solve::<GiantSquid>,
+    },
+    Puzzle {
+        day: 5,
+        part: 1,
+        name: "hydrothermal-venture",
+        input: "day-05/hydrothermal-venture/data/puzzle_input.dat",
+        solve: solve::<HydrothermalVenture>,
+    },
+    Puzzle {
+        day: 5,
+        part: 2,
+        name: "hydrothermal-venture-part-two",
+        input: "day-05/part-two/data/puzzle_input.dat",
+        solve: solve::<HydrothermalVenture>,
+    },
+];
+
+// Day which can be benchmarked by `aoc bench`.
+pub struct Day {
+    pub day: u8,
+    // Bundled puzzle input, relative to the workspace root.
+    pub input: &'static str,
+    pub bench: fn(&str, usize) -> aoc_common::Result<DayBenchmark>,
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        input: "day-01/sonar-sweep/input.dat",
+        bench: bench_day::<SonarSweep>,
+    },
+    Day {
+        day: 2,
+        input: "day-02/dive/input.dat",
+        bench: bench_day::<Dive>,
+    },
+    Day {
+        day: 3,
+        input: "day-03/binary-diagnostic/data/puzzle_input.dat",
+        bench: bench_day::<BinaryDiagnostic>,
+    },
+    Day {
+        day: 4,
+        input: "day-04/giant-squid/data/puzzle_input.dat",
+        bench: bench_day::<GiantSquid>,
+    },
+    Day {
+        day: 5,
+        input: "day-05/hydrothermal-venture/data/puzzle_input.dat",
+        bench: bench_day::<HydrothermalVenture>,
+    },
+];
+
+pub fn find_puzzle(day: &str, part: &str) -> Result<&'static Puzzle, String> {
+    let day = day
+        .parse::<u8>()
+        .map_err(|_| format!("incorrect day: '{}'", day))?;
+    let part = part
+        .parse::<u8>()
+        .map_err(|_| format!("incorrect part: '{}'", part))?;
+    PUZZLES
+        .iter()
+        .find(|puzzle| puzzle.day == day && puzzle.part == part)
+        .ok_or_else(|| format!("no solution for day {} part {}", day, part))
+}
+
+pub fn workspace_root() -> &'static Path {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir.parent().unwrap_or(manifest_dir)
+}
+
+pub fn bundled_input(puzzle: &Puzzle) -> InputSource {
+    InputSource::File(workspace_root().join(puzzle.input))
+}
+
+// Returns answer and its JSON record.
+fn solve<S: Solution>(source: &InputSource, part: u8) -> (aoc_common::Result<Answer>, Json) {
+    let start = Instant::now();
+    let answer = S::parse_input(source).and_then(|input| S::solve(&input, part));
+    let elapsed = start.elapsed();
+    let record = match &answer {
+        Ok(answer) => answer_record::<S>(part, source, answer, elapsed),
+        Err(e) => error_record::<S>(part, source, e),
+    };
+    (answer, record)
+}
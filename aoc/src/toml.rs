@@ -0,0 +1,137 @@
+use aoc_common::{column_of, Error, Result};
+use std::str::FromStr;
+
+// Minimal TOML subset used by `aoc` files: arrays of tables
+// (`[[name]]`) with `key = value` pairs, where value is an integer or
+// a basic string. Values are parsed when they are read from the table.
+pub struct Table {
+    // Line of the `[[name]]` header.
+    pub line: usize,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    key: String,
+    raw_value: String,
+    line: usize,
+    column: usize,
+}
+
+impl Table {
+    fn field(&self, key: &str) -> Result<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.key == key)
+            .ok_or_else(|| Error::parse(self.line, 1, &format!("'{}' field", key), ""))
+    }
+
+    pub fn integer<T>(&self, key: &str) -> Result<T>
+    where
+        T: FromStr,
+    {
+        let field = self.field(key)?;
+        field
+            .raw_value
+            .parse::<T>()
+            .map_err(|_| Error::parse(field.line, field.column, "integer", &field.raw_value))
+    }
+
+    pub fn string(&self, key: &str) -> Result<String> {
+        let field = self.field(key)?;
+        let error = || Error::parse(field.line, field.column, "quoted string", &field.raw_value);
+        let inner = field
+            .raw_value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .ok_or_else(error)?;
+
+        let mut result = String::with_capacity(inner.len());
+        let mut letters = inner.chars();
+        while let Some(letter) = letters.next() {
+            match letter {
+                '\\' => match letters.next() {
+                    Some('"') => result.push('"'),
+                    Some('\\') => result.push('\\'),
+                    _ => return Err(error()),
+                },
+                '"' => return Err(error()),
+                letter => result.push(letter),
+            }
+        }
+        Ok(result)
+    }
+}
+
+// Parse all `[[name]]` tables. Keys which are not in `keys` are errors.
+pub fn parse_tables(text: &str, name: &str, keys: &[&str]) -> Result<Vec<Table>> {
+    let header = format!("[[{}]]", name);
+    let mut tables = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed == header {
+            tables.push(Table {
+                line: line_number,
+                fields: Vec::new(),
+            });
+            continue;
+        }
+
+        let table = tables
+            .last_mut()
+            .ok_or_else(|| Error::parse(line_number, 1, &format!("'{}'", header), trimmed))?;
+        let (key, value) = trimmed
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .ok_or_else(|| Error::parse(line_number, 1, "'key = value'", trimmed))?;
+        if !keys.contains(&key) {
+            let expected = keys.join(", ");
+            return Err(Error::parse(
+                line_number,
+                column_of(line, key),
+                &expected,
+                key,
+            ));
+        }
+        table.fields.push(Field {
+            key: key.to_owned(),
+            raw_value: value.to_owned(),
+            line: line_number,
+            column: column_of(line, value),
+        });
+    }
+    Ok(tables)
+}
+
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables() {
+        let text = "# comment\n[[item]]\nname = \"a \\\"b\\\"\"\nsize = 3\n\n[[item]]\nsize = 4\n";
+        let tables = parse_tables(text, "item", &["name", "size"]).unwrap();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].string("name").unwrap(), "a \"b\"");
+        assert_eq!(tables[0].integer::<u8>("size").unwrap(), 3);
+        assert_eq!(tables[1].integer::<u8>("size").unwrap(), 4);
+        assert_eq!(
+            tables[1].string("name").unwrap_err().to_string(),
+            "6:1: expected 'name' field, found \"\""
+        );
+
+        let error = parse_tables("[[item]]\ncolor = 1\n", "item", &["name", "size"]);
+        assert_eq!(
+            error.err().unwrap().to_string(),
+            "2:1: expected name, size, found \"color\""
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Error shared by all days.
+//
+// Parse errors point at the offending text, example:
+//   input.dat:417:9: expected integer, found "12a"
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    Parse {
+        path: Option<PathBuf>,
+        // Line and column are counted from 1.
+        line: usize,
+        column: usize,
+        expected: String,
+        found: String,
+    },
+    // Input is correct, but the puzzle can't be solved for it.
+    Solve(String),
+    // Incorrect command line arguments.
+    Usage(String),
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, expected: &str, found: &str) -> Self {
+        Error::Parse {
+            path: None,
+            line,
+            column,
+            expected: expected.to_owned(),
+            found: found.to_owned(),
+        }
+    }
+
+    pub fn solve(message: &str) -> Self {
+        Error::Solve(message.to_owned())
+    }
+
+    // Attach file path to the error (if it has no path yet).
+    pub fn with_path(mut self, file_path: &Path) -> Self {
+        match &mut self {
+            Error::Io { path, .. } | Error::Parse { path, .. } if path.is_none() => {
+                *path = Some(file_path.to_owned());
+            }
+            _ => (),
+        }
+        self
+    }
+
+    // Path of the file the error is in, if it is known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::Io { path, .. } | Error::Parse { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    // Move parse error to the given line. Used when single line is parsed
+    // without knowledge about its position in the file.
+    pub fn on_line(mut self, line_number: usize) -> Self {
+        if let Error::Parse { line, .. } = &mut self {
+            *line = line_number;
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "{}: {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "{}", source),
+            Error::Parse {
+                path,
+                line,
+                column,
+                expected,
+                found,
+            } => {
+                if let Some(path) = path {
+                    write!(f, "{}:", path.display())?;
+                }
+                write!(
+                    f,
+                    "{}:{}: expected {}, found {:?}",
+                    line, column, expected, found
+                )
+            }
+            Error::Solve(message) | Error::Usage(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+// Returns 1-based column of `token`, which has to be a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+// Parse `token` (slice of `line`) into number.
+pub fn parse_integer<T>(line: &str, token: &str, line_number: usize) -> Result<T>
+where
+    T: FromStr,
+{
+    token
+        .parse::<T>()
+        .map_err(|_| Error::parse(line_number, column_of(line, token), "integer", token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_with_path() {
+        let line = "forward 12a";
+        let error = parse_integer::<usize>(line, &line[8..], 417)
+            .unwrap_err()
+            .with_path(Path::new("input.dat"));
+        assert_eq!(
+            error.to_string(),
+            "input.dat:417:9: expected integer, found \"12a\""
+        );
+        assert_eq!(error.path(), Some(Path::new("input.dat")));
+        let error = Error::solve("no answer").with_path(Path::new("input.dat"));
+        assert_eq!(error.path(), None);
+    }
+
+    #[test]
+    fn parse_error_on_line() {
+        let error = Error::parse(1, 3, "binary digit", "2").on_line(7);
+        assert_eq!(error.to_string(), "7:3: expected binary digit, found \"2\"");
+    }
+}
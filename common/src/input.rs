@@ -0,0 +1,85 @@
+use crate::{Error, Result};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+// Place from which puzzle input is read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    // Convert command line arguments (without name of binary) into input
+    // sources. No argument or `-` means standard input.
+    pub fn from_args(args: &[String]) -> Vec<InputSource> {
+        if args.is_empty() {
+            return vec![InputSource::Stdin];
+        }
+        args.iter()
+            .map(|arg| match arg.as_str() {
+                "-" => InputSource::Stdin,
+                file_name => InputSource::File(PathBuf::from(file_name)),
+            })
+            .collect()
+    }
+
+    // Path used in error messages.
+    pub fn path(&self) -> &Path {
+        match self {
+            InputSource::Stdin => Path::new("<stdin>"),
+            InputSource::File(path) => path,
+        }
+    }
+
+    pub fn read(&self) -> Result<String> {
+        let mut input = String::new();
+        match self {
+            InputSource::Stdin => io::stdin()
+                .read_to_string(&mut input)
+                .map(|_| input)
+                .map_err(|e| Error::from(e).with_path(self.path())),
+            InputSource::File(path) => {
+                fs::read_to_string(path).map_err(|e| Error::from(e).with_path(path))
+            }
+        }
+    }
+
+    // Buffered reader for input which doesn't fit in memory or never ends.
+    pub fn reader(&self) -> Result<Box<dyn BufRead>> {
+        match self {
+            InputSource::Stdin => Ok(Box::new(io::stdin().lock())),
+            InputSource::File(path) => fs::File::open(path)
+                .map(|file| Box::new(BufReader::new(file)) as Box<dyn BufRead>)
+                .map_err(|e| Error::from(e).with_path(path)),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path().display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_from_args() {
+        assert_eq!(InputSource::from_args(&[]), vec![InputSource::Stdin]);
+
+        let args = ["a.dat".to_owned(), "-".to_owned(), "b.dat".to_owned()];
+        assert_eq!(
+            InputSource::from_args(&args),
+            vec![
+                InputSource::File(PathBuf::from("a.dat")),
+                InputSource::Stdin,
+                InputSource::File(PathBuf::from("b.dat")),
+            ]
+        );
+    }
+}
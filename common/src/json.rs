@@ -0,0 +1,175 @@
+use std::fmt;
+
+// Minimal JSON value, enough to print results for other tools.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    // Make empty object. Fields are added with `Json::field`.
+    pub fn object() -> Self {
+        Json::Object(Vec::new())
+    }
+
+    pub fn field<T>(mut self, name: &str, value: T) -> Self
+    where
+        T: Into<Json>,
+    {
+        if let Json::Object(fields) = &mut self {
+            fields.push((name.to_owned(), value.into()));
+        }
+        self
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Integer(value) => write!(f, "{}", value),
+            Json::Float(value) if value.is_finite() => write!(f, "{}", value),
+            Json::Float(_) => write!(f, "null"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for letter in value.chars() {
+        match letter {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            letter if (letter as u32) < 0x20 => write!(f, "\\u{:04x}", letter as u32)?,
+            letter => write!(f, "{}", letter)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Json::Integer(value)
+    }
+}
+
+// Integer when it fits into `i64`, decimal string otherwise.
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Json::Integer(value),
+            Err(_) => Json::String(value.to_string()),
+        }
+    }
+}
+
+impl From<u8> for Json {
+    fn from(value: u8) -> Self {
+        Json::Integer(i64::from(value))
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Json::Float(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl<T> From<Vec<T>> for Json
+where
+    T: Into<Json>,
+{
+    fn from(items: Vec<T>) -> Self {
+        Json::Array(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T> From<Option<T>> for Json
+where
+    T: Into<Json>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object() {
+        let json = Json::object()
+            .field("day", 3u8)
+            .field("title", "Binary \"Diagnostic\"")
+            .field("ratings", vec![1991usize, 3982])
+            .field("board", Option::<usize>::None);
+        assert_eq!(
+            json.to_string(),
+            r#"{"day":3,"title":"Binary \"Diagnostic\"","ratings":[1991,3982],"board":null}"#
+        );
+    }
+
+    #[test]
+    fn large_integers() {
+        let json = Json::object()
+            .field("max", i64::MAX as usize)
+            .field("above", usize::MAX);
+        assert_eq!(
+            json.to_string(),
+            r#"{"max":9223372036854775807,"above":"18446744073709551615"}"#
+        );
+        let value = crate::Value::from(usize::MAX);
+        assert_eq!(Json::from(&value).to_string(), r#""18446744073709551615""#);
+    }
+}
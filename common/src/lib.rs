@@ -0,0 +1,173 @@
+use std::fmt;
+use std::ops::Index;
+
+mod error;
+mod input;
+mod json;
+mod runner;
+mod svg;
+
+pub use error::{column_of, parse_integer, Error, Result};
+pub use input::InputSource;
+pub use json::Json;
+pub use runner::{
+    answer_record, error_record, run_part, run_reports, run_sources, run_with, take_flag,
+    take_option, take_parsed_option, OutputFormat,
+};
+pub use svg::{svg_header, xml_escape};
+
+// Common interface of every day. Both parts of a day share one parser
+// and one type of parsed input.
+pub trait Solution {
+    type Input;
+
+    const DAY: u8;
+    const TITLE: &'static str;
+
+    // Parse whole puzzle input (content of the input file).
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_one(input: &Self::Input) -> Result<Answer>;
+
+    fn part_two(input: &Self::Input) -> Result<Answer>;
+
+    // Read and parse puzzle input. Errors point at the input file.
+    fn parse_input(source: &InputSource) -> Result<Self::Input> {
+        let input = source.read()?;
+        Self::parse(&input).map_err(|e| e.with_path(source.path()))
+    }
+
+    // Solve selected part (1 or 2) for parsed input.
+    fn solve(input: &Self::Input, part: u8) -> Result<Answer> {
+        match part {
+            1 => Self::part_one(input),
+            2 => Self::part_two(input),
+            _ => Err(Error::Solve(format!("incorrect part: {}", part))),
+        }
+    }
+}
+
+// Answer for one part of the puzzle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    pub value: Value,
+    // Intermediate values, e.g. gamma and epsilon rate.
+    pub details: Vec<(&'static str, Value)>,
+}
+
+impl Answer {
+    pub fn with<T>(mut self, name: &'static str, value: T) -> Self
+    where
+        T: Into<Value>,
+    {
+        self.details.push((name, value.into()));
+        self
+    }
+
+    pub fn detail(&self, name: &str) -> Option<&Value> {
+        self.details
+            .iter()
+            .find(|(detail_name, _)| *detail_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+// Panics when answer has no detail with given name (like `HashMap`).
+impl Index<&str> for Answer {
+    type Output = Value;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        self.detail(name)
+            .unwrap_or_else(|| panic!("answer has no detail '{}'", name))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<T> From<T> for Answer
+where
+    T: Into<Value>,
+{
+    fn from(value: T) -> Self {
+        Answer {
+            value: value.into(),
+            details: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    // Precision of the format applies, e.g. `{:.3}`.
+    Float(f64),
+    Text(String),
+    // Structured detail, printed as JSON in text too.
+    Json(Json),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Float(value) => fmt::Display::fmt(value, f),
+            Value::Text(value) => write!(f, "{}", value),
+            Value::Json(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<isize> for Value {
+    fn from(value: isize) -> Self {
+        Value::Integer(value as i64)
+    }
+}
+
+// Integer when it fits into `i64`, decimal text otherwise.
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Value::Integer(value),
+            Err(_) => Value::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<Json> for Value {
+    fn from(value: Json) -> Self {
+        Value::Json(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<&Value> for Json {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Integer(value) => Json::Integer(*value),
+            Value::Float(value) => Json::Float(*value),
+            Value::Text(value) => Json::String(value.clone()),
+            Value::Json(value) => value.clone(),
+        }
+    }
+}
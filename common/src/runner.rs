@@ -0,0 +1,246 @@
+use crate::{Answer, Error, InputSource, Json, Result, Solution};
+use std::env;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    // One JSON object per line.
+    Json,
+}
+
+impl OutputFormat {
+    // Remove `--format <text|json>` from arguments and return selected
+    // format. Text is the default format.
+    pub fn take_from_args(args: &mut Vec<String>) -> Result<OutputFormat> {
+        match take_option(args, "--format")?.as_deref() {
+            None | Some("text") => Ok(OutputFormat::Text),
+            Some("json") => Ok(OutputFormat::Json),
+            Some(format) => Err(Error::Usage(format!(
+                "unknown output format: '{}', expected text or json",
+                format
+            ))),
+        }
+    }
+}
+
+// Remove option `name` and its value from arguments and return the value.
+pub fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    let position = match args.iter().position(|arg| arg == name) {
+        Some(position) => position,
+        None => return Ok(None),
+    };
+    if position + 1 >= args.len() {
+        return Err(Error::Usage(format!("option '{}' requires value", name)));
+    }
+    let value = args.remove(position + 1);
+    args.remove(position);
+    Ok(Some(value))
+}
+
+// Remove flag `name` from arguments and return whether it was given.
+pub fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    let length = args.len();
+    args.retain(|arg| arg != name);
+    args.len() != length
+}
+
+// Like `take_option`, but the value is parsed.
+pub fn take_parsed_option<T>(args: &mut Vec<String>, name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+{
+    take_option(args, name)?
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| Error::Usage(format!("incorrect value of '{}': '{}'", name, value)))
+        })
+        .transpose()
+}
+
+// Main loop shared by binaries. Command line arguments:
+//   [--format text|json] [file_name]...
+// where `-` (or no file name) is standard input.
+//
+// In text format `describe` makes printed text from the answer. When more
+// than one input is given, every printed line and error is prefixed with the
+// input name. Returns failure when solving of any input failed.
+pub fn run_part<S, F>(part: u8, describe: F) -> ExitCode
+where
+    S: Solution,
+    F: Fn(&Answer) -> String,
+{
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    run_with::<S, _, _>(args, part, |input| S::solve(input, part), describe)
+}
+
+// Like `run_part`, but the part is solved by `solve` (e.g. configured by
+// options which were already taken from `args`).
+pub fn run_with<S, G, F>(args: Vec<String>, part: u8, solve: G, describe: F) -> ExitCode
+where
+    S: Solution,
+    G: Fn(&S::Input) -> Result<Answer>,
+    F: Fn(&Answer) -> String,
+{
+    run_sources::<S, _, _>(
+        args,
+        part,
+        |source, _| S::parse_input(source).and_then(|input| solve(&input)),
+        describe,
+    )
+}
+
+// Like `run_with`, but `solve` reads the input source itself (e.g. when
+// input is parsed differently than by `S::parse`). It also gets selected
+// output format in case it prints anything on its own.
+pub fn run_sources<S, G, F>(args: Vec<String>, part: u8, solve: G, describe: F) -> ExitCode
+where
+    S: Solution,
+    G: Fn(&InputSource, OutputFormat) -> Result<Answer>,
+    F: Fn(&Answer) -> String,
+{
+    run_reports::<S, _, _, _, _>(args, part, solve, describe, Answer::clone)
+}
+
+// Like `run_sources`, but `solve` makes a report of any type. Text is made
+// by `describe` from the report itself, JSON from the answer made by
+// `answer`.
+pub fn run_reports<S, T, G, F, A>(
+    mut args: Vec<String>,
+    part: u8,
+    solve: G,
+    describe: F,
+    answer: A,
+) -> ExitCode
+where
+    S: Solution,
+    G: Fn(&InputSource, OutputFormat) -> Result<T>,
+    F: Fn(&T) -> String,
+    A: Fn(&T) -> Answer,
+{
+    let format = match OutputFormat::take_from_args(&mut args) {
+        Ok(format) => format,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let sources = InputSource::from_args(&args);
+
+    let mut failed = false;
+    for source in &sources {
+        let start = Instant::now();
+        let report = solve(source, format).map_err(|e| e.with_path(source.path()));
+        let elapsed = start.elapsed();
+
+        match (report, format) {
+            (Ok(report), OutputFormat::Text) if sources.len() == 1 => {
+                println!("{}", describe(&report))
+            }
+            (Ok(report), OutputFormat::Text) => describe(&report)
+                .lines()
+                .for_each(|line| println!("{}: {}", source, line)),
+            (Ok(report), OutputFormat::Json) => {
+                let answer = answer(&report);
+                println!("{}", answer_record::<S>(part, source, &answer, elapsed))
+            }
+            (Err(e), OutputFormat::Text) => {
+                failed = true;
+                // Errors without a path are prefixed like the answers.
+                match e.path() {
+                    None if sources.len() > 1 => eprintln!("{}: {}", source, e),
+                    _ => eprintln!("{}", e),
+                }
+            }
+            (Err(e), OutputFormat::Json) => {
+                failed = true;
+                println!("{}", error_record::<S>(part, source, &e));
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// JSON object describing one solved part.
+pub fn answer_record<S: Solution>(
+    part: u8,
+    source: &InputSource,
+    answer: &Answer,
+    elapsed: Duration,
+) -> Json {
+    let details = answer
+        .details
+        .iter()
+        .fold(Json::object(), |acc, (name, value)| acc.field(name, value));
+    Json::object()
+        .field("day", S::DAY)
+        .field("part", part)
+        .field("title", S::TITLE)
+        .field("input", source.to_string())
+        .field("answer", &answer.value)
+        .field("details", details)
+        .field("elapsed_ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+// JSON object describing failed part.
+pub fn error_record<S: Solution>(part: u8, source: &InputSource, error: &Error) -> Json {
+    Json::object()
+        .field("day", S::DAY)
+        .field("part", part)
+        .field("title", S::TITLE)
+        .field("input", source.to_string())
+        .field("error", error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn format_from_args() {
+        let mut arguments = args(&["a.dat", "--format", "json", "b.dat"]);
+        let format = OutputFormat::take_from_args(&mut arguments).unwrap();
+        assert_eq!(format, OutputFormat::Json);
+        assert_eq!(arguments, args(&["a.dat", "b.dat"]));
+
+        let mut arguments = args(&["a.dat"]);
+        let format = OutputFormat::take_from_args(&mut arguments).unwrap();
+        assert_eq!(format, OutputFormat::Text);
+
+        let mut arguments = args(&["--format", "xml"]);
+        assert!(OutputFormat::take_from_args(&mut arguments).is_err());
+    }
+
+    #[test]
+    fn parsed_option() {
+        let mut arguments = args(&["--window", "3", "a.dat"]);
+        let window = take_parsed_option::<usize>(&mut arguments, "--window").unwrap();
+        assert_eq!(window, Some(3));
+        assert_eq!(arguments, args(&["a.dat"]));
+
+        let mut arguments = args(&["--window", "x"]);
+        assert!(take_parsed_option::<usize>(&mut arguments, "--window").is_err());
+        let mut arguments = args(&["--window"]);
+        assert!(take_parsed_option::<usize>(&mut arguments, "--window").is_err());
+    }
+
+    #[test]
+    fn flag() {
+        let mut arguments = args(&["a.dat", "--stream"]);
+        assert!(take_flag(&mut arguments, "--stream"));
+        assert!(!take_flag(&mut arguments, "--stream"));
+        assert_eq!(arguments, args(&["a.dat"]));
+    }
+}
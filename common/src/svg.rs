@@ -0,0 +1,47 @@
+use std::fmt::Write;
+
+// Escape text for SVG content and attribute values.
+pub fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Start of a standalone SVG document: the root element, white background
+// and `title` at the top left, `margin` from the left edge. The document is
+// closed by `</svg>`.
+pub fn svg_header(title: &str, width: f64, height: f64, margin: f64) -> String {
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+        w = width,
+        h = height
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="20" font-size="14">{}</text>"#,
+        margin,
+        xml_escape(title)
+    )
+    .unwrap();
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header() {
+        assert_eq!(xml_escape(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+        let svg = svg_header("Depth <1>", 300.0, 200.0, 50.0);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="300" height="200""#)
+        );
+        assert!(svg.ends_with("<text x=\"50\" y=\"20\" font-size=\"14\">Depth &lt;1&gt;</text>\n"));
+    }
+}
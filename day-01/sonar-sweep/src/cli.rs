@@ -0,0 +1,104 @@
+use crate::readings::{run_readings, ReadingFormat};
+use crate::render::{is_svg, run_render};
+use crate::report::run_report;
+use crate::stream::run_stream;
+use crate::{describe, SonarSweep, WindowConfig};
+use aoc_common::{run_with, take_flag, take_option, Error, Result};
+use std::env;
+use std::process::ExitCode;
+
+// Modes of day 1 binaries.
+enum Mode {
+    Answer,
+    Stream,
+    Report,
+    // Sparkline or SVG.
+    Render { svg: bool },
+}
+
+// Main of both binaries, `default` is window configuration of the part.
+// Command line arguments:
+//   [--format text|json] [--window N] [--aggregate sum|mean|min|max|median]
+//   [--type unsigned|signed|float] [--timestamps [--max-span SECONDS]]
+//   [--stream [--progress N] | --report [--outliers zscore|mad] [--threshold X]
+//    | --render sparkline|svg [--width N]]
+//   [file_name]...
+// Input is read from standard input when file name is `-` or not given.
+// With `--stream` inputs are read line by line in constant memory, with
+// `--report` statistics of the depth series are printed instead of answer
+// and `--render` draws the depth series.
+pub fn run(part: u8, default: WindowConfig) -> ExitCode {
+    let mut args = env::args().skip(1).collect::<Vec<String>>();
+    let (config, format, mode) = match take_options(&mut args, default) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match mode {
+        Mode::Report => run_report(args, part, config),
+        Mode::Stream => run_stream(args, part, config),
+        Mode::Render { svg } => run_render(args, config, svg),
+        Mode::Answer if format != ReadingFormat::PUZZLE => run_readings(args, part, config, format),
+        Mode::Answer => run_with::<SonarSweep, _, _>(
+            args,
+            part,
+            |measurements| Ok(config.answer(measurements)),
+            describe,
+        ),
+    }
+}
+
+fn take_options(
+    args: &mut Vec<String>,
+    default: WindowConfig,
+) -> Result<(WindowConfig, ReadingFormat, Mode)> {
+    let config = WindowConfig::take_from_args(args, default)?;
+    let format = ReadingFormat::take_from_args(args)?;
+    let render = take_option(args, "--render")?;
+    let modes = [
+        take_flag(args, "--stream").then_some(Mode::Stream),
+        take_flag(args, "--report").then_some(Mode::Report),
+        render
+            .map(|name| is_svg(&name).map(|svg| Mode::Render { svg }))
+            .transpose()?,
+    ];
+    let mut modes = modes.into_iter().flatten();
+    let mode = modes.next().unwrap_or(Mode::Answer);
+    if modes.next().is_some() {
+        return Err(Error::Usage(String::from(
+            "only one of '--stream', '--report' and '--render' can be given",
+        )));
+    }
+    if !matches!(mode, Mode::Answer) && format != ReadingFormat::PUZZLE {
+        return Err(Error::Usage(String::from(
+            "options '--type' and '--timestamps' can only be used when solving",
+        )));
+    }
+    Ok((config, format, mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readings_only_when_solving() {
+        // Report, stream and rendering read unsigned measurements only.
+        for mode in ["--report", "--stream"] {
+            let mut args = vec![
+                String::from(mode),
+                String::from("--type"),
+                String::from("signed"),
+            ];
+            let error = take_options(&mut args, WindowConfig::PART_ONE)
+                .err()
+                .unwrap();
+            assert_eq!(
+                error.to_string(),
+                "options '--type' and '--timestamps' can only be used when solving"
+            );
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use aoc_common::{column_of, take_parsed_option, Answer, Error, Result, Solution};
+use std::fmt;
+use std::str::FromStr;
+
+pub mod cli;
+pub mod readings;
+pub mod render;
+pub mod report;
+pub mod stream;
+
+pub struct SonarSweep;
+
+impl Solution for SonarSweep {
+    type Input = Vec<usize>;
+
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Sonar Sweep";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_measurements(input)
+    }
+
+    fn part_one(measurements: &Self::Input) -> Result<Answer> {
+        Ok(WindowConfig::PART_ONE.answer(measurements))
+    }
+
+    fn part_two(measurements: &Self::Input) -> Result<Answer> {
+        Ok(WindowConfig::PART_TWO.answer(measurements))
+    }
+}
+
+// Value of a depth reading. Aggregates of integer readings are compared
+// exactly, floating point is used only for float readings and charts.
+pub trait Depth: Copy + PartialOrd + fmt::Display {
+    // What is expected in parse errors.
+    const EXPECTED: &'static str;
+
+    fn parse(token: &str) -> Option<Self>;
+
+    fn to_f64(self) -> f64;
+
+    // Exact value of integer readings, `None` for floats.
+    fn to_i128(self) -> Option<i128>;
+}
+
+impl Depth for usize {
+    const EXPECTED: &'static str = "integer";
+
+    fn parse(token: &str) -> Option<Self> {
+        token.parse().ok()
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        Some(self as i128)
+    }
+}
+
+impl Depth for i64 {
+    const EXPECTED: &'static str = "signed integer";
+
+    fn parse(token: &str) -> Option<Self> {
+        token.parse().ok()
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        Some(self as i128)
+    }
+}
+
+impl Depth for f64 {
+    const EXPECTED: &'static str = "number";
+
+    // Infinity and NaN would make every comparison meaningless.
+    fn parse(token: &str) -> Option<Self> {
+        token.parse::<f64>().ok().filter(|value| value.is_finite())
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        None
+    }
+}
+
+// How measurements of one sliding window are combined before comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Median,
+}
+
+impl Aggregate {
+    pub const ALL: [Aggregate; 5] = [
+        Aggregate::Sum,
+        Aggregate::Mean,
+        Aggregate::Min,
+        Aggregate::Max,
+        Aggregate::Median,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Mean => "mean",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Median => "median",
+        }
+    }
+
+    // Window must not be empty.
+    pub fn apply<T: Depth>(&self, window: &[T]) -> f64 {
+        let values = window.iter().map(|value| value.to_f64());
+        match self {
+            Aggregate::Sum => values.sum(),
+            Aggregate::Mean => values.sum::<f64>() / window.len() as f64,
+            Aggregate::Min => values.fold(f64::INFINITY, f64::min),
+            Aggregate::Max => values.fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Median => {
+                let mut sorted = values.collect::<Vec<f64>>();
+                sorted.sort_unstable_by(f64::total_cmp);
+                let middle = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    (sorted[middle - 1] + sorted[middle]) / 2.0
+                } else {
+                    sorted[middle]
+                }
+            }
+        }
+    }
+
+    // Aggregate of integer readings as fraction `(numerator, denominator)`,
+    // `None` for float readings or empty window.
+    fn exact<T: Depth>(&self, window: &[T]) -> Option<(i128, i128)> {
+        let mut values = window
+            .iter()
+            .map(|value| value.to_i128())
+            .collect::<Option<Vec<i128>>>()?;
+        match self {
+            Aggregate::Sum => Some((values.iter().sum(), 1)),
+            Aggregate::Mean => Some((values.iter().sum(), values.len() as i128)),
+            Aggregate::Min => values.iter().min().map(|&value| (value, 1)),
+            Aggregate::Max => values.iter().max().map(|&value| (value, 1)),
+            Aggregate::Median if values.is_empty() => None,
+            Aggregate::Median => {
+                values.sort_unstable();
+                let middle = values.len() / 2;
+                if values.len().is_multiple_of(2) {
+                    Some((values[middle - 1] + values[middle], 2))
+                } else {
+                    Some((values[middle], 1))
+                }
+            }
+        }
+    }
+
+    // Whether aggregate of `next` is greater than aggregate of `previous`.
+    // Integer readings are compared exactly (`f64` loses precision above
+    // 2^53), fractions by cross multiplication.
+    pub fn increases<T: Depth>(&self, previous: &[T], next: &[T]) -> bool {
+        if let (Some((a, a_denominator)), Some((b, b_denominator))) =
+            (self.exact(previous), self.exact(next))
+        {
+            if let (Some(a), Some(b)) = (a.checked_mul(b_denominator), b.checked_mul(a_denominator))
+            {
+                return a < b;
+            }
+        }
+        self.apply(previous) < self.apply(next)
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Aggregate {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Aggregate::ALL
+            .iter()
+            .find(|aggregate| aggregate.name() == name)
+            .copied()
+            .ok_or_else(|| {
+                let names = Aggregate::ALL.map(|aggregate| aggregate.name());
+                Error::Usage(format!(
+                    "unknown aggregate: '{}', expected one of: {}",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+// Configuration of the sliding-window comparison. Both puzzle parts are
+// just particular configurations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowConfig {
+    pub window: usize,
+    pub aggregate: Aggregate,
+}
+
+impl WindowConfig {
+    pub const PART_ONE: WindowConfig = WindowConfig {
+        window: 1,
+        aggregate: Aggregate::Sum,
+    };
+    pub const PART_TWO: WindowConfig = WindowConfig {
+        window: 3,
+        aggregate: Aggregate::Sum,
+    };
+
+    // Take `--window N` and `--aggregate NAME` options from arguments,
+    // values not given are taken from `default`.
+    pub fn take_from_args(args: &mut Vec<String>, default: WindowConfig) -> Result<WindowConfig> {
+        let window = take_parsed_option(args, "--window")?.unwrap_or(default.window);
+        if window == 0 {
+            return Err(Error::Usage(String::from("window size must be positive")));
+        }
+        let aggregate = take_parsed_option(args, "--aggregate")?.unwrap_or(default.aggregate);
+        Ok(WindowConfig { window, aggregate })
+    }
+
+    pub fn answer<T: Depth>(&self, measurements: &[T]) -> Answer {
+        self.count_answer(count_incremental_measurements(
+            measurements,
+            self.window,
+            self.aggregate,
+        ))
+    }
+
+    // Answer with count computed elsewhere (e.g. from a stream).
+    pub fn count_answer(&self, count: usize) -> Answer {
+        Answer::from(count)
+            .with("window", self.window)
+            .with("aggregate", self.aggregate.to_string())
+    }
+}
+
+// Text printed by binaries.
+pub fn describe(answer: &Answer) -> String {
+    format!("Incremental measurements: {}", answer)
+}
+
+// Count windows of `window` measurements whose aggregate is greater than
+// the aggregate of the preceding window. Window of size 1 compares
+// adjacent measurements.
+pub fn count_incremental_measurements<T: Depth>(
+    measurements: &[T],
+    window: usize,
+    aggregate: Aggregate,
+) -> usize {
+    if window == 0 {
+        return 0;
+    }
+    measurements
+        .windows(window + 1)
+        .filter(|item| aggregate.increases(&item[..window], &item[1..]))
+        .count()
+}
+
+pub fn parse_measurements<T: Depth>(input: &str) -> Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_depth(line, line.trim(), i + 1))
+        .collect()
+}
+
+fn parse_depth<T: Depth>(line: &str, token: &str, line_number: usize) -> Result<T> {
+    T::parse(token)
+        .ok_or_else(|| Error::parse(line_number, column_of(line, token), T::EXPECTED, token))
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::Aggregate;
+
+    const EXAMPLE: [usize; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_data() {
+        let incremental_measurements =
+            super::count_incremental_measurements(&EXAMPLE, 1, Aggregate::Sum);
+        assert_eq!(incremental_measurements, 7);
+    }
+
+    #[test]
+    fn example_data_window_sums() {
+        let incremental_measurements =
+            super::count_incremental_measurements(&EXAMPLE, 3, Aggregate::Sum);
+        assert_eq!(incremental_measurements, 5);
+    }
+
+    #[test]
+    fn example_data_aggregates() {
+        let count =
+            |window, aggregate| super::count_incremental_measurements(&EXAMPLE, window, aggregate);
+        // Mean orders windows of the same size exactly like sum.
+        assert_eq!(count(3, Aggregate::Mean), 5);
+        assert_eq!(count(3, Aggregate::Min), 4);
+        assert_eq!(count(3, Aggregate::Max), 3);
+        assert_eq!(count(3, Aggregate::Median), 4);
+        assert_eq!(count(2, Aggregate::Median), count(2, Aggregate::Mean));
+        assert_eq!(count(10, Aggregate::Sum), 0);
+        assert_eq!(count(0, Aggregate::Sum), 0);
+    }
+
+    #[test]
+    fn aggregate_values() {
+        let window: [usize; 4] = [3, 1, 4, 1];
+        assert_eq!(Aggregate::Sum.apply(&window), 9.0);
+        assert_eq!(Aggregate::Mean.apply(&window), 2.25);
+        assert_eq!(Aggregate::Min.apply(&window), 1.0);
+        assert_eq!(Aggregate::Max.apply(&window), 4.0);
+        assert_eq!(Aggregate::Median.apply(&window), 2.0);
+        assert_eq!(Aggregate::Median.apply(&window[..3]), 3.0);
+    }
+
+    #[test]
+    fn exact_comparison() {
+        // Both readings are the same `f64`.
+        let input = "9007199254740992\n9007199254740993\n";
+        let unsigned = super::parse_measurements::<usize>(input).unwrap();
+        let signed = super::parse_measurements::<i64>(input).unwrap();
+        for aggregate in Aggregate::ALL {
+            assert_eq!(
+                super::count_incremental_measurements(&unsigned, 1, aggregate),
+                1
+            );
+            assert_eq!(
+                super::count_incremental_measurements(&signed, 1, aggregate),
+                1
+            );
+        }
+        let large = [usize::MAX - 2, usize::MAX, usize::MAX - 1, usize::MAX];
+        assert!(Aggregate::Sum.increases(&large[..2], &large[2..]));
+        assert!(Aggregate::Median.increases(&large[..3], &large[1..]));
+        assert!(!Aggregate::Mean.increases(&large[1..3], &large[..2]));
+    }
+
+    #[test]
+    fn signed_and_float_measurements() {
+        let signed = super::parse_measurements::<i64>("-3\n-1\n-2\n0\n").unwrap();
+        assert_eq!(
+            super::count_incremental_measurements(&signed, 1, Aggregate::Sum),
+            2
+        );
+        let float = super::parse_measurements::<f64>("201.5\n201.25\n201.75\n").unwrap();
+        assert_eq!(
+            super::count_incremental_measurements(&float, 1, Aggregate::Sum),
+            1
+        );
+
+        let error = super::parse_measurements::<i64>("1\n1.5\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: expected signed integer, found \"1.5\""
+        );
+        let error = super::parse_measurements::<f64>("1\nNaN\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:1: expected number, found \"NaN\"");
+    }
+
+    #[test]
+    fn window_options() {
+        let mut args = vec![String::from("--aggregate"), String::from("max")];
+        let config = super::WindowConfig::take_from_args(&mut args, super::WindowConfig::PART_TWO);
+        assert_eq!(
+            config.unwrap(),
+            super::WindowConfig {
+                window: 3,
+                aggregate: Aggregate::Max
+            }
+        );
+        assert!(args.is_empty());
+
+        let mut args = vec![String::from("--window"), String::from("0")];
+        let config = super::WindowConfig::take_from_args(&mut args, super::WindowConfig::PART_ONE);
+        assert!(config.is_err());
+        assert!("average".parse::<Aggregate>().is_err());
+    }
+
+    #[test]
+    fn malformed_measurement() {
+        let error = super::parse_measurements::<usize>("199\n200\n 2o8\n").unwrap_err();
+        assert_eq!(error.to_string(), "3:2: expected integer, found \"2o8\"");
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{describe, parse_depth, parse_measurements, Depth, SonarSweep, WindowConfig};
+use aoc_common::{
+    column_of, run_sources, take_flag, take_option, take_parsed_option, Answer, Error, Result,
+};
+use std::process::ExitCode;
+
+// Type of depth values in input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberType {
+    Unsigned,
+    Signed,
+    Float,
+}
+
+impl NumberType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberType::Unsigned => "unsigned",
+            NumberType::Signed => "signed",
+            NumberType::Float => "float",
+        }
+    }
+}
+
+// Format of input lines: depth alone or `timestamp,depth` CSV rows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReadingFormat {
+    pub number: NumberType,
+    pub timestamps: bool,
+    // Only windows whose readings span at most this many seconds are
+    // compared (requires timestamps).
+    pub max_span: Option<f64>,
+}
+
+impl ReadingFormat {
+    // Format of the puzzle input.
+    pub const PUZZLE: ReadingFormat = ReadingFormat {
+        number: NumberType::Unsigned,
+        timestamps: false,
+        max_span: None,
+    };
+
+    // Take `--type unsigned|signed|float`, `--timestamps` and
+    // `--max-span SECONDS` options from arguments.
+    pub fn take_from_args(args: &mut Vec<String>) -> Result<ReadingFormat> {
+        let number = match take_option(args, "--type")?.as_deref() {
+            None | Some("unsigned") => NumberType::Unsigned,
+            Some("signed") => NumberType::Signed,
+            Some("float") => NumberType::Float,
+            Some(name) => {
+                return Err(Error::Usage(format!(
+                    "unknown number type: '{}', expected unsigned, signed or float",
+                    name
+                )))
+            }
+        };
+        let timestamps = take_flag(args, "--timestamps");
+        let max_span = take_parsed_option::<f64>(args, "--max-span")?;
+        if max_span.is_some() && !timestamps {
+            return Err(Error::Usage(String::from(
+                "option '--max-span' requires '--timestamps'",
+            )));
+        }
+        Ok(ReadingFormat {
+            number,
+            timestamps,
+            max_span,
+        })
+    }
+
+    pub fn count<T: Depth>(&self, input: &str, config: WindowConfig) -> Result<usize> {
+        let readings = parse_readings::<T>(input, self.timestamps)?;
+        Ok(count_incremental_readings(&readings, config, self.max_span))
+    }
+}
+
+// Depth readings with optional timestamps (seconds since Unix epoch).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Readings<T> {
+    pub depths: Vec<T>,
+    pub timestamps: Option<Vec<f64>>,
+}
+
+// With `timestamps` every line is a `timestamp,depth` row, where timestamp
+// is a number of seconds or an ISO 8601 UTC date and time (for example
+// `2021-12-01T08:15:30Z`). Optional `timestamp,depth` header is skipped.
+// Timestamps must not decrease.
+pub fn parse_readings<T: Depth>(input: &str, timestamps: bool) -> Result<Readings<T>> {
+    if !timestamps {
+        return Ok(Readings {
+            depths: parse_measurements(input)?,
+            timestamps: None,
+        });
+    }
+
+    let mut depths = Vec::new();
+    let mut times = Vec::<f64>::new();
+    for (i, line) in input.lines().enumerate() {
+        if i == 0
+            && line
+                .replace(' ', "")
+                .eq_ignore_ascii_case("timestamp,depth")
+        {
+            continue;
+        }
+        let (time, depth) = line
+            .split_once(',')
+            .ok_or_else(|| Error::parse(i + 1, 1, "timestamp,depth", line))?;
+        let time = time.trim();
+        let timestamp = parse_timestamp(time)
+            .ok_or_else(|| Error::parse(i + 1, column_of(line, time), "timestamp", time))?;
+        if let Some(previous) = times.last().filter(|&&previous| previous > timestamp) {
+            return Err(Error::parse(
+                i + 1,
+                column_of(line, time),
+                &format!("timestamp not earlier than {}", previous),
+                time,
+            ));
+        }
+        times.push(timestamp);
+        depths.push(parse_depth(line, depth.trim(), i + 1)?);
+    }
+    Ok(Readings {
+        depths,
+        timestamps: Some(times),
+    })
+}
+
+// Seconds since Unix epoch, given directly or as ISO 8601 UTC date and time.
+pub fn parse_timestamp(token: &str) -> Option<f64> {
+    if let Ok(seconds) = token.parse::<f64>() {
+        return seconds.is_finite().then_some(seconds);
+    }
+    let (date, time) = token.split_once(['T', ' '])?;
+    let time = time.strip_suffix('Z').unwrap_or(time);
+
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let mut time = time.splitn(3, ':');
+    let hour = time.next()?.parse::<i64>().ok()?;
+    let minute = time.next()?.parse::<i64>().ok()?;
+    let second = time.next()?.parse::<f64>().ok()?;
+
+    let days_in_month = match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if !(1..=days_in_month).contains(&day)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0.0..61.0).contains(&second)
+    {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    Some((days * 86400 + hour * 3600 + minute * 60) as f64 + second)
+}
+
+// Days since 1970-01-01 in proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// Like `count_incremental_measurements`, but with `max_span` the compared
+// windows are skipped when their readings are more than `max_span` seconds
+// apart (e.g. across a gap in the recording).
+pub fn count_incremental_readings<T: Depth>(
+    readings: &Readings<T>,
+    config: WindowConfig,
+    max_span: Option<f64>,
+) -> usize {
+    let window = config.window;
+    if window == 0 {
+        return 0;
+    }
+    let within_span = |i: usize| match (&readings.timestamps, max_span) {
+        (Some(timestamps), Some(span)) => timestamps[i + window] - timestamps[i] <= span,
+        _ => true,
+    };
+    readings
+        .depths
+        .windows(window + 1)
+        .enumerate()
+        .filter(|&(i, item)| {
+            within_span(i) && config.aggregate.increases(&item[..window], &item[1..])
+        })
+        .count()
+}
+
+// Main loop of binaries when input isn't in the puzzle format.
+pub fn run_readings(
+    args: Vec<String>,
+    part: u8,
+    config: WindowConfig,
+    format: ReadingFormat,
+) -> ExitCode {
+    run_sources::<SonarSweep, _, _>(
+        args,
+        part,
+        |source, _| {
+            let input = source.read()?;
+            let count = match format.number {
+                NumberType::Unsigned => format.count::<usize>(&input, config),
+                NumberType::Signed => format.count::<i64>(&input, config),
+                NumberType::Float => format.count::<f64>(&input, config),
+            };
+            count
+                .map(|count| answer(count, config, format))
+                .map_err(|e| e.with_path(source.path()))
+        },
+        describe,
+    )
+}
+
+fn answer(count: usize, config: WindowConfig, format: ReadingFormat) -> Answer {
+    let answer = config
+        .count_answer(count)
+        .with("type", String::from(format.number.name()));
+    match format.max_span {
+        Some(span) => answer.with("max_span", span.to_string()),
+        None => answer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Aggregate;
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(parse_timestamp("1638346530"), Some(1638346530.0));
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0.0));
+        assert_eq!(parse_timestamp("2021-12-01T08:15:30Z"), Some(1638346530.0));
+        assert_eq!(parse_timestamp("2021-12-01 08:15:30.5"), Some(1638346530.5));
+        assert_eq!(parse_timestamp("2000-02-29T00:00:00Z"), Some(951782400.0));
+        assert_eq!(parse_timestamp("2021-02-29T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2021-12-01T24:00:00Z"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn timestamped_readings() {
+        let input = "timestamp,depth\n0,-1.5\n10,-1.0\n20,-2.0\n100,-0.5\n110,0.5\n";
+        let readings = parse_readings::<f64>(input, true).unwrap();
+        assert_eq!(readings.depths, vec![-1.5, -1.0, -2.0, -0.5, 0.5]);
+        assert_eq!(
+            readings.timestamps,
+            Some(vec![0.0, 10.0, 20.0, 100.0, 110.0])
+        );
+
+        let config = WindowConfig {
+            window: 1,
+            aggregate: Aggregate::Sum,
+        };
+        assert_eq!(count_incremental_readings(&readings, config, None), 3);
+        // Increase from 20 s to 100 s is across a gap.
+        assert_eq!(count_incremental_readings(&readings, config, Some(30.0)), 2);
+
+        let input = "0,9007199254740992\n1,9007199254740993\n";
+        let readings = parse_readings::<i64>(input, true).unwrap();
+        assert_eq!(count_incremental_readings(&readings, config, None), 1);
+    }
+
+    #[test]
+    fn malformed_readings() {
+        let error = parse_readings::<i64>("0,1\n5,2.5\n", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:3: expected signed integer, found \"2.5\""
+        );
+        let error = parse_readings::<i64>("0,1\n5 2\n", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: expected timestamp,depth, found \"5 2\""
+        );
+        let error = parse_readings::<i64>("0,1\nnoon,2\n", true).unwrap_err();
+        assert_eq!(error.to_string(), "2:1: expected timestamp, found \"noon\"");
+        let error = parse_readings::<i64>("10,1\n5,2\n", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: expected timestamp not earlier than 10, found \"5\""
+        );
+    }
+
+    #[test]
+    fn format_options() {
+        let mut args = ["--type", "float", "--timestamps", "--max-span", "60"]
+            .map(String::from)
+            .to_vec();
+        let format = ReadingFormat::take_from_args(&mut args).unwrap();
+        assert_eq!(format.number, NumberType::Float);
+        assert_eq!(format.max_span, Some(60.0));
+        assert!(args.is_empty());
+
+        let mut args = ["--max-span", "60"].map(String::from).to_vec();
+        assert!(ReadingFormat::take_from_args(&mut args).is_err());
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{SonarSweep, WindowConfig};
+use aoc_common::{
+    svg_header, take_parsed_option, xml_escape, Error, InputSource, OutputFormat, Result, Solution,
+};
+use std::fmt::Write;
+use std::process::ExitCode;
+
+// Series of values with the points counted as increases (greater than the
+// previous value) marked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub values: Vec<f64>,
+    pub increases: Vec<bool>,
+}
+
+impl Series {
+    pub fn new(name: String, values: Vec<f64>) -> Self {
+        let increases = (0..values.len())
+            .map(|i| i > 0 && values[i - 1] < values[i])
+            .collect();
+        Series {
+            name,
+            values,
+            increases,
+        }
+    }
+
+    pub fn depths(measurements: &[usize]) -> Self {
+        let values = measurements.iter().map(|&value| value as f64).collect();
+        let mut series = Series::new(String::from("Depth"), values);
+        // Exactly, values are rounded above 2^53.
+        series.increases = (0..measurements.len())
+            .map(|i| i > 0 && measurements[i - 1] < measurements[i])
+            .collect();
+        series
+    }
+
+    // Aggregates of sliding windows. Number of increases is the answer of
+    // `count_incremental_measurements` with the same configuration.
+    pub fn windows(measurements: &[usize], config: WindowConfig) -> Self {
+        let windows = measurements
+            .windows(config.window.max(1))
+            .collect::<Vec<&[usize]>>();
+        let values = windows
+            .iter()
+            .map(|window| config.aggregate.apply(window))
+            .collect();
+        let name = format!("Window {} {}", config.window, config.aggregate);
+        let mut series = Series::new(name, values);
+        series.increases = (0..windows.len())
+            .map(|i| i > 0 && config.aggregate.increases(windows[i - 1], windows[i]))
+            .collect();
+        series
+    }
+
+    pub fn count_increases(&self) -> usize {
+        self.increases.iter().filter(|&&increase| increase).count()
+    }
+
+    fn range(&self) -> (f64, f64) {
+        self.values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    }
+}
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Sparkline of at most `width` columns. Every column is the mean of
+// consecutive values. The line below it marks columns in which all (`+`)
+// or some (`.`) values are increases.
+pub fn sparkline(series: &Series, width: usize) -> String {
+    let (min, max) = series.range();
+    let mut text = format!(
+        "{} ({} values, {}..{}, {} increases)",
+        series.name,
+        series.values.len(),
+        min,
+        max,
+        series.count_increases()
+    );
+    if series.values.is_empty() {
+        return text;
+    }
+
+    let per_column = series.values.len().div_ceil(width.max(1));
+    let mut line = String::new();
+    let mut marks = String::new();
+    for (values, increases) in series
+        .values
+        .chunks(per_column)
+        .zip(series.increases.chunks(per_column))
+    {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let level = if max > min {
+            ((mean - min) / (max - min) * (LEVELS.len() - 1) as f64).round() as usize
+        } else {
+            0
+        };
+        line.push(LEVELS[level]);
+        marks.push(
+            match increases.iter().filter(|&&increase| increase).count() {
+                0 => ' ',
+                count if count == increases.len() => '+',
+                _ => '.',
+            },
+        );
+    }
+    write!(text, "\n{}\n{}", line, marks.trim_end()).unwrap();
+    text
+}
+
+const PANEL_HEIGHT: f64 = 240.0;
+const MARGIN: f64 = 50.0;
+
+// Standalone SVG document with one panel per series. Like on a depth profile
+// greater values are drawn lower; increases are marked with red dots.
+pub fn svg(title: &str, series: &[Series], width: usize) -> String {
+    let width = width.max(100) as f64;
+    let plot_width = width - 2.0 * MARGIN;
+    let height = MARGIN + series.len() as f64 * (PANEL_HEIGHT + MARGIN);
+
+    let mut svg = svg_header(title, width, height, MARGIN);
+
+    for (panel, series) in series.iter().enumerate() {
+        let top = MARGIN + panel as f64 * (PANEL_HEIGHT + MARGIN);
+        let (min, max) = series.range();
+        let x = |i: usize| {
+            let last = series.values.len().saturating_sub(1).max(1);
+            MARGIN + i as f64 / last as f64 * plot_width
+        };
+        let y = |value: f64| {
+            if max > min {
+                top + (value - min) / (max - min) * PANEL_HEIGHT
+            } else {
+                top + PANEL_HEIGHT / 2.0
+            }
+        };
+
+        writeln!(
+            svg,
+            r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#999"/>"##,
+            MARGIN, top, plot_width, PANEL_HEIGHT
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}">{} ({} increases)</text>"#,
+            MARGIN,
+            top - 8.0,
+            xml_escape(&series.name),
+            series.count_increases()
+        )
+        .unwrap();
+        if series.values.is_empty() {
+            continue;
+        }
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            MARGIN - 4.0,
+            top + 12.0,
+            min
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            MARGIN - 4.0,
+            top + PANEL_HEIGHT,
+            max
+        )
+        .unwrap();
+
+        let points = series
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| format!("{:.1},{:.1}", x(i), y(value)))
+            .collect::<Vec<String>>();
+        writeln!(
+            svg,
+            r##"<polyline fill="none" stroke="#1f77b4" stroke-width="1" points="{}"/>"##,
+            points.join(" ")
+        )
+        .unwrap();
+        writeln!(svg, r##"<g fill="#d62728">"##).unwrap();
+        for (i, &value) in series.values.iter().enumerate() {
+            if series.increases[i] {
+                writeln!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="1.5"/>"#,
+                    x(i),
+                    y(value)
+                )
+                .unwrap();
+            }
+        }
+        writeln!(svg, "</g>").unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Depths and, unless the window is a single measurement, window aggregates.
+pub fn series(measurements: &[usize], config: WindowConfig) -> Vec<Series> {
+    let mut series = vec![Series::depths(measurements)];
+    if config.window > 1 {
+        series.push(Series::windows(measurements, config));
+    }
+    series
+}
+
+// Main loop of binaries in rendering mode, remaining command line arguments:
+//   [--width N] [file_name]...
+// Sparklines are `--width` columns wide (80 by default). SVG is `--width`
+// pixels wide (800 by default) and printed to standard output, so only
+// one input is allowed.
+pub fn run_render(mut args: Vec<String>, config: WindowConfig, svg_output: bool) -> ExitCode {
+    let width = OutputFormat::take_from_args(&mut args).and_then(|format| {
+        if format == OutputFormat::Json {
+            return Err(Error::Usage(String::from("rendering has no JSON output")));
+        }
+        take_parsed_option::<usize>(&mut args, "--width")
+    });
+    let width = match width {
+        Ok(width) => width.unwrap_or(if svg_output { 800 } else { 80 }),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let sources = InputSource::from_args(&args);
+    if svg_output && sources.len() > 1 {
+        eprintln!("SVG can be rendered for one input only");
+        return ExitCode::FAILURE;
+    }
+
+    let mut failed = false;
+    for source in &sources {
+        let measurements = match SonarSweep::parse_input(source) {
+            Ok(measurements) => measurements,
+            Err(e) => {
+                failed = true;
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        let series = series(&measurements, config);
+        if svg_output {
+            let title = format!("{}: {}", SonarSweep::TITLE, source);
+            print!("{}", svg(&title, &series, width));
+            continue;
+        }
+        if sources.len() > 1 {
+            println!("{}:", source);
+        }
+        for series in &series {
+            println!("{}", sparkline(series, width));
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// Used by option parsing in `cli`.
+pub(crate) fn is_svg(name: &str) -> Result<bool> {
+    match name {
+        "sparkline" => Ok(false),
+        "svg" => Ok(true),
+        name => Err(Error::Usage(format!(
+            "unknown rendering: '{}', expected sparkline or svg",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count_incremental_measurements;
+
+    const EXAMPLE: [usize; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn increases_match_count() {
+        for config in [WindowConfig::PART_ONE, WindowConfig::PART_TWO] {
+            let series = Series::windows(&EXAMPLE, config);
+            assert_eq!(
+                series.count_increases(),
+                count_incremental_measurements(&EXAMPLE, config.window, config.aggregate)
+            );
+        }
+        let series = Series::windows(&EXAMPLE, WindowConfig::PART_TWO);
+        assert_eq!(series.values[..3], [607.0, 618.0, 618.0]);
+    }
+
+    #[test]
+    fn example_sparkline() {
+        let series = Series::depths(&EXAMPLE);
+        assert_eq!(
+            sparkline(&series, 80),
+            "Depth (10 values, 199..269, 7 increases)\n▁▁▂▂▁▂▅█▇▇\n +++ +++ +"
+        );
+        // Two values per column.
+        assert_eq!(
+            sparkline(&series, 5).lines().skip(1).collect::<Vec<&str>>(),
+            vec!["▁▂▁▇▇", ".+.+."]
+        );
+    }
+
+    #[test]
+    fn example_svg() {
+        let svg = svg("a < b", &series(&EXAMPLE, WindowConfig::PART_TWO), 800);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("a &lt; b"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 7 + 5);
+    }
+}
@@ -0,0 +1,501 @@
+use crate::{count_incremental_measurements, Aggregate, SonarSweep, WindowConfig};
+use aoc_common::{
+    run_reports, take_option, take_parsed_option, Answer, Error, Json, Result, Solution,
+};
+use std::fmt;
+use std::process::ExitCode;
+
+// Method of flagging outliers with its threshold.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutlierMethod {
+    // Distance from mean in standard deviations.
+    ZScore(f64),
+    // Modified z-score: 0.6745 * distance from median in median absolute
+    // deviations (robust against the outliers themselves).
+    Mad(f64),
+}
+
+impl OutlierMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutlierMethod::ZScore(_) => "zscore",
+            OutlierMethod::Mad(_) => "mad",
+        }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        match self {
+            OutlierMethod::ZScore(threshold) | OutlierMethod::Mad(threshold) => *threshold,
+        }
+    }
+
+    // Take `--outliers zscore|mad` and `--threshold X` options from arguments.
+    pub fn take_from_args(args: &mut Vec<String>) -> Result<OutlierMethod> {
+        let threshold = take_parsed_option::<f64>(args, "--threshold")?;
+        match take_option(args, "--outliers")?.as_deref() {
+            None | Some("zscore") => Ok(OutlierMethod::ZScore(threshold.unwrap_or(3.0))),
+            Some("mad") => Ok(OutlierMethod::Mad(threshold.unwrap_or(3.5))),
+            Some(method) => Err(Error::Usage(format!(
+                "unknown outlier method: '{}', expected zscore or mad",
+                method
+            ))),
+        }
+    }
+
+    // Score of every measurement, `None` when measurements don't vary.
+    fn scores(&self, measurements: &[usize]) -> Option<Vec<f64>> {
+        let values = measurements.iter().map(|&value| value as f64);
+        match self {
+            OutlierMethod::ZScore(_) => {
+                let count = measurements.len() as f64;
+                let mean = values.clone().sum::<f64>() / count;
+                let variance = values
+                    .clone()
+                    .map(|value| (value - mean).powi(2))
+                    .sum::<f64>()
+                    / count;
+                let deviation = variance.sqrt();
+                (deviation > 0.0).then(|| values.map(|value| (value - mean) / deviation).collect())
+            }
+            OutlierMethod::Mad(_) => {
+                let center = median(values.clone().collect());
+                let mad = median(values.clone().map(|value| (value - center).abs()).collect());
+                (mad > 0.0).then(|| {
+                    values
+                        .map(|value| 0.6745 * (value - center) / mad)
+                        .collect()
+                })
+            }
+        }
+    }
+}
+
+fn median(values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    Aggregate::Median.apply(&values)
+}
+
+impl fmt::Display for OutlierMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} > {}", self.name(), self.threshold())
+    }
+}
+
+// Change between two consecutive measurements, `line` is the line of the
+// second one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Change {
+    pub line: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Change {
+    // Wider than the measurements, so it can't overflow.
+    pub fn difference(&self) -> i128 {
+        self.to as i128 - self.from as i128
+    }
+
+    fn to_json(self) -> Json {
+        // Integer when it fits into `i64` like other JSON integers.
+        let difference = match i64::try_from(self.difference()) {
+            Ok(difference) => Json::Integer(difference),
+            Err(_) => Json::String(self.difference().to_string()),
+        };
+        Json::object()
+            .field("difference", difference)
+            .field("line", self.line)
+            .field("from", self.from)
+            .field("to", self.to)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:+} on line {} ({} -> {})",
+            self.difference(),
+            self.line,
+            self.from,
+            self.to
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outlier {
+    pub line: usize,
+    pub measurement: usize,
+    pub score: f64,
+}
+
+impl Outlier {
+    fn to_json(self) -> Json {
+        Json::object()
+            .field("line", self.line)
+            .field("measurement", self.measurement)
+            .field("score", self.score)
+    }
+}
+
+// Statistics of the depth series. Lines are 1-based like in parse errors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub measurements: usize,
+    pub config: WindowConfig,
+    pub incremental_measurements: usize,
+    // First line and length of the longest strictly increasing run.
+    pub longest_increasing_run: (usize, usize),
+    pub largest_jump: Option<Change>,
+    pub largest_drop: Option<Change>,
+    // Runs of equal consecutive readings and number of equal pairs in them.
+    pub plateaus: usize,
+    pub equal_readings: usize,
+    pub outlier_method: OutlierMethod,
+    pub outliers: Vec<Outlier>,
+}
+
+impl Report {
+    pub fn new(
+        measurements: &[usize],
+        config: WindowConfig,
+        outlier_method: OutlierMethod,
+    ) -> Self {
+        let changes = measurements
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| Change {
+                line: i + 2,
+                from: pair[0],
+                to: pair[1],
+            })
+            .collect::<Vec<Change>>();
+
+        let mut longest_increasing_run = (1, measurements.len().min(1));
+        let mut run = longest_increasing_run;
+        let mut plateaus = 0;
+        let mut equal_readings = 0;
+        let mut previous_equal = false;
+        for change in &changes {
+            if change.to > change.from {
+                run.1 += 1;
+            } else {
+                run = (change.line, 1);
+            }
+            if run.1 > longest_increasing_run.1 {
+                longest_increasing_run = run;
+            }
+
+            let equal = change.to == change.from;
+            if equal {
+                equal_readings += 1;
+                if !previous_equal {
+                    plateaus += 1;
+                }
+            }
+            previous_equal = equal;
+        }
+
+        // The first of equal changes is reported.
+        let largest_jump = changes
+            .iter()
+            .filter(|change| change.difference() > 0)
+            .fold(None, |largest: Option<&Change>, change| match largest {
+                Some(largest) if largest.difference() >= change.difference() => Some(largest),
+                _ => Some(change),
+            })
+            .copied();
+        let largest_drop = changes
+            .iter()
+            .filter(|change| change.difference() < 0)
+            .fold(None, |largest: Option<&Change>, change| match largest {
+                Some(largest) if largest.difference() <= change.difference() => Some(largest),
+                _ => Some(change),
+            })
+            .copied();
+
+        let outliers = outlier_method
+            .scores(measurements)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, score)| score.abs() > outlier_method.threshold())
+            .map(|(i, score)| Outlier {
+                line: i + 1,
+                measurement: measurements[i],
+                score,
+            })
+            .collect();
+
+        Report {
+            measurements: measurements.len(),
+            config,
+            incremental_measurements: count_incremental_measurements(
+                measurements,
+                config.window,
+                config.aggregate,
+            ),
+            longest_increasing_run,
+            largest_jump,
+            largest_drop,
+            plateaus,
+            equal_readings,
+            outlier_method,
+            outliers,
+        }
+    }
+
+    // Number of incremental measurements with the rest of the report as
+    // details.
+    pub fn answer(&self) -> Answer {
+        let outliers = self
+            .outliers
+            .iter()
+            .map(|outlier| outlier.to_json())
+            .collect::<Vec<Json>>();
+        self.config
+            .count_answer(self.incremental_measurements)
+            .with("measurements", self.measurements)
+            .with(
+                "longest_increasing_run",
+                Json::object()
+                    .field("line", self.longest_increasing_run.0)
+                    .field("length", self.longest_increasing_run.1),
+            )
+            .with(
+                "largest_jump",
+                Json::from(self.largest_jump.map(Change::to_json)),
+            )
+            .with(
+                "largest_drop",
+                Json::from(self.largest_drop.map(Change::to_json)),
+            )
+            .with("plateaus", self.plateaus)
+            .with("equal_readings", self.equal_readings)
+            .with("outlier_method", self.outlier_method.name().to_string())
+            .with(
+                "outlier_threshold",
+                Json::from(self.outlier_method.threshold()),
+            )
+            .with("outliers", Json::from(outliers))
+    }
+}
+
+// Text printed by binaries in report mode.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let change = |change: Option<Change>| {
+            change.map_or(String::from("none"), |change| change.to_string())
+        };
+        writeln!(f, "Measurements: {}", self.measurements)?;
+        writeln!(
+            f,
+            "Incremental measurements (window {}, {}): {}",
+            self.config.window,
+            self.config.aggregate.name(),
+            self.incremental_measurements
+        )?;
+        writeln!(
+            f,
+            "Longest increasing run: {} measurements from line {}",
+            self.longest_increasing_run.1, self.longest_increasing_run.0
+        )?;
+        writeln!(f, "Largest jump: {}", change(self.largest_jump))?;
+        writeln!(f, "Largest drop: {}", change(self.largest_drop))?;
+        writeln!(
+            f,
+            "Plateaus: {} ({} equal consecutive readings)",
+            self.plateaus, self.equal_readings
+        )?;
+        write!(f, "Outliers ({}):", self.outlier_method)?;
+        if self.outliers.is_empty() {
+            write!(f, " none")?;
+        }
+        for outlier in &self.outliers {
+            write!(
+                f,
+                "\n  line {}: {} (score {:.2})",
+                outlier.line, outlier.measurement, outlier.score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Main loop of binaries in report mode, remaining command line arguments:
+//   [--format text|json] [--outliers zscore|mad] [--threshold X] [file_name]...
+pub fn run_report(mut args: Vec<String>, part: u8, config: WindowConfig) -> ExitCode {
+    let outlier_method = match OutlierMethod::take_from_args(&mut args) {
+        Ok(method) => method,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    run_reports::<SonarSweep, _, _, _, _>(
+        args,
+        part,
+        |source, _| {
+            SonarSweep::parse_input(source)
+                .map(|measurements| Report::new(&measurements, config, outlier_method))
+        },
+        Report::to_string,
+        Report::answer,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [usize; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_report() {
+        let report = Report::new(&EXAMPLE, WindowConfig::PART_ONE, OutlierMethod::ZScore(3.0));
+        assert_eq!(report.incremental_measurements, 7);
+        assert_eq!(report.longest_increasing_run, (1, 4));
+        assert_eq!(
+            report.largest_jump,
+            Some(Change {
+                line: 7,
+                from: 207,
+                to: 240
+            })
+        );
+        assert_eq!(
+            report.largest_drop.map(|change| change.difference()),
+            Some(-10)
+        );
+        assert_eq!(report.largest_drop.map(|change| change.line), Some(5));
+        assert_eq!((report.plateaus, report.equal_readings), (0, 0));
+        assert!(report.outliers.is_empty());
+    }
+
+    #[test]
+    fn plateaus() {
+        let report = Report::new(
+            &[1, 1, 1, 2, 2, 3, 2],
+            WindowConfig::PART_ONE,
+            OutlierMethod::ZScore(3.0),
+        );
+        assert_eq!((report.plateaus, report.equal_readings), (2, 3));
+        assert_eq!(report.longest_increasing_run, (3, 2));
+
+        let report = Report::new(&[], WindowConfig::PART_ONE, OutlierMethod::ZScore(3.0));
+        assert_eq!(report.longest_increasing_run, (1, 0));
+        assert_eq!(report.largest_jump, None);
+    }
+
+    #[test]
+    fn outliers() {
+        let measurements = [10, 12, 11, 9, 10, 100, 13];
+        let report = Report::new(
+            &measurements,
+            WindowConfig::PART_ONE,
+            OutlierMethod::Mad(3.5),
+        );
+        let lines = report
+            .outliers
+            .iter()
+            .map(|outlier| outlier.line)
+            .collect::<Vec<usize>>();
+        assert_eq!(lines, vec![6]);
+        assert!((report.outliers[0].score - 0.6745 * 89.0).abs() < 1e-9);
+
+        let report = Report::new(
+            &measurements,
+            WindowConfig::PART_ONE,
+            OutlierMethod::ZScore(2.0),
+        );
+        assert_eq!(report.outliers.len(), 1);
+        assert_eq!(report.outliers[0].measurement, 100);
+
+        // Constant series has no outliers.
+        let report = Report::new(&[5, 5, 5], WindowConfig::PART_ONE, OutlierMethod::Mad(3.5));
+        assert!(report.outliers.is_empty());
+    }
+
+    #[test]
+    fn report_answer() {
+        let report = Report::new(&EXAMPLE, WindowConfig::PART_TWO, OutlierMethod::Mad(3.0));
+        assert_eq!(
+            report.to_string(),
+            "Measurements: 10
+Incremental measurements (window 3, sum): 5
+Longest increasing run: 4 measurements from line 1
+Largest jump: +33 on line 7 (207 -> 240)
+Largest drop: -10 on line 5 (210 -> 200)
+Plateaus: 0 (0 equal consecutive readings)
+Outliers (mad > 3):
+  line 8: 269 (score 4.26)
+  line 9: 260 (score 3.62)
+  line 10: 263 (score 3.83)"
+        );
+        let report = Report::new(&[], WindowConfig::PART_ONE, OutlierMethod::ZScore(3.0));
+        let text = report.to_string();
+        assert!(text.contains("Largest jump: none\n"));
+        assert!(text.ends_with("Outliers (zscore > 3): none"));
+    }
+
+    #[test]
+    fn report_json() {
+        let report = Report::new(&EXAMPLE, WindowConfig::PART_ONE, OutlierMethod::Mad(4.0));
+        let answer = report.answer();
+        assert_eq!(
+            answer["largest_jump"].to_string(),
+            r#"{"difference":33,"line":7,"from":207,"to":240}"#
+        );
+        assert_eq!(
+            answer["longest_increasing_run"].to_string(),
+            r#"{"line":1,"length":4}"#
+        );
+        let outliers = Json::from(&answer["outliers"]);
+        assert!(outliers
+            .to_string()
+            .starts_with(r#"[{"line":8,"measurement":269,"score":4.26"#));
+        assert_eq!(Json::from(&answer["outlier_threshold"]), Json::Float(4.0));
+
+        let report = Report::new(&[], WindowConfig::PART_ONE, OutlierMethod::ZScore(3.0));
+        let answer = report.answer();
+        assert_eq!(Json::from(&answer["largest_drop"]), Json::Null);
+        assert_eq!(Json::from(&answer["outliers"]), Json::Array(Vec::new()));
+    }
+
+    #[test]
+    fn wide_changes() {
+        let measurements = [9223372036854775807, 9223372036854775808, 0];
+        let report = Report::new(
+            &measurements,
+            WindowConfig::PART_ONE,
+            OutlierMethod::ZScore(3.0),
+        );
+        assert_eq!(
+            report.largest_jump.map(|change| change.difference()),
+            Some(1)
+        );
+        assert_eq!(
+            report.largest_drop.map(|change| change.difference()),
+            Some(-9223372036854775808)
+        );
+    }
+
+    #[test]
+    fn outlier_options() {
+        let mut args = vec![String::from("--outliers"), String::from("mad")];
+        assert_eq!(
+            OutlierMethod::take_from_args(&mut args).unwrap(),
+            OutlierMethod::Mad(3.5)
+        );
+        let mut args = vec![String::from("--threshold"), String::from("2.5")];
+        assert_eq!(
+            OutlierMethod::take_from_args(&mut args).unwrap(),
+            OutlierMethod::ZScore(2.5)
+        );
+        let mut args = vec![String::from("--outliers"), String::from("iqr")];
+        assert!(OutlierMethod::take_from_args(&mut args).is_err());
+    }
+}
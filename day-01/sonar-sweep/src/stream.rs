@@ -0,0 +1,212 @@
+use crate::{describe, SonarSweep, WindowConfig};
+use aoc_common::{
+    parse_integer, run_sources, take_parsed_option, Error, InputSource, Json, OutputFormat, Result,
+    Solution,
+};
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::process::ExitCode;
+
+// Measurements parsed line by line, only the current line is kept in memory.
+pub struct Measurements<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> Measurements<R> {
+    pub fn new(reader: R) -> Self {
+        Measurements {
+            reader,
+            line: String::new(),
+            line_number: 0,
+        }
+    }
+
+    // Number of the last read line.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: BufRead> Iterator for Measurements<R> {
+    type Item = Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.line.clear();
+        match self.reader.read_line(&mut self.line) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                let line = self.line.trim_end_matches(['\n', '\r']);
+                Some(parse_integer(line, line.trim(), self.line_number))
+            }
+            Err(e) => Some(Err(Error::from(e))),
+        }
+    }
+}
+
+// Sliding-window comparison over a stream. Only the last `window + 1`
+// measurements are kept, so memory doesn't depend on length of the stream.
+pub struct WindowCounter {
+    config: WindowConfig,
+    buffer: VecDeque<usize>,
+    count: usize,
+}
+
+impl WindowCounter {
+    pub fn new(config: WindowConfig) -> Self {
+        WindowCounter {
+            config,
+            buffer: VecDeque::with_capacity(config.window + 1),
+            count: 0,
+        }
+    }
+
+    // Add next measurement and return the running count.
+    pub fn push(&mut self, measurement: usize) -> usize {
+        let window = self.config.window;
+        if window == 0 {
+            return self.count;
+        }
+        if self.buffer.len() > window {
+            self.buffer.pop_front();
+        }
+        self.buffer.push_back(measurement);
+        if self.buffer.len() > window {
+            let aggregate = self.config.aggregate;
+            let values = self.buffer.make_contiguous();
+            if aggregate.increases(&values[..window], &values[1..]) {
+                self.count += 1;
+            }
+        }
+        self.count
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+// Same answer as `count_incremental_measurements`, but the reader is
+// consumed line by line.
+pub fn count_stream<R: BufRead>(reader: R, config: WindowConfig) -> Result<usize> {
+    let mut counter = WindowCounter::new(config);
+    for measurement in Measurements::new(reader) {
+        counter.push(measurement?);
+    }
+    Ok(counter.count())
+}
+
+// Main loop of binaries in streaming mode, remaining command line arguments:
+//   [--format text|json] [--progress N] [file_name]...
+// Every input is read line by line. With `--progress N` the running count
+// is printed after every N measurements (useful for endless inputs).
+pub fn run_stream(mut args: Vec<String>, part: u8, config: WindowConfig) -> ExitCode {
+    let progress = match take_parsed_option::<usize>(&mut args, "--progress") {
+        Ok(progress) => progress.filter(|&every| every > 0),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    run_sources::<SonarSweep, _, _>(
+        args,
+        part,
+        |source, format| {
+            stream_source(source, part, config, format, progress)
+                .map(|count| config.count_answer(count))
+                .map_err(|e| e.with_path(source.path()))
+        },
+        describe,
+    )
+}
+
+fn stream_source(
+    source: &InputSource,
+    part: u8,
+    config: WindowConfig,
+    format: OutputFormat,
+    progress: Option<usize>,
+) -> Result<usize> {
+    let mut measurements = Measurements::new(source.reader()?);
+    let mut counter = WindowCounter::new(config);
+    while let Some(measurement) = measurements.next() {
+        let count = counter.push(measurement?);
+        let line = measurements.line_number();
+        match (progress, format) {
+            (Some(every), OutputFormat::Text) if line % every == 0 => {
+                println!("{}:{}: running count {}", source, line, count)
+            }
+            (Some(every), OutputFormat::Json) if line % every == 0 => println!(
+                "{}",
+                Json::object()
+                    .field("day", SonarSweep::DAY)
+                    .field("part", part)
+                    .field("input", source.to_string())
+                    .field("line", line)
+                    .field("running_count", count)
+            ),
+            _ => (),
+        }
+    }
+    Ok(counter.count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_incremental_measurements, parse_measurements, Aggregate};
+
+    const INPUT: &str = include_str!("../input.dat");
+
+    #[test]
+    fn same_answers_as_slices() {
+        let measurements = parse_measurements::<usize>(INPUT).unwrap();
+        for window in [1, 2, 3, 7] {
+            for aggregate in Aggregate::ALL {
+                let config = WindowConfig { window, aggregate };
+                assert_eq!(
+                    count_stream(INPUT.as_bytes(), config).unwrap(),
+                    count_incremental_measurements(&measurements, window, aggregate),
+                    "window {}, aggregate {}",
+                    window,
+                    aggregate
+                );
+            }
+        }
+        assert_eq!(
+            count_stream(INPUT.as_bytes(), WindowConfig::PART_ONE).unwrap(),
+            1400
+        );
+        assert_eq!(
+            count_stream(INPUT.as_bytes(), WindowConfig::PART_TWO).unwrap(),
+            1429
+        );
+    }
+
+    #[test]
+    fn running_counts() {
+        let mut counter = WindowCounter::new(WindowConfig::PART_TWO);
+        let counts = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263]
+            .iter()
+            .map(|&measurement| counter.push(measurement))
+            .collect::<Vec<usize>>();
+        assert_eq!(counts, vec![0, 0, 0, 1, 1, 1, 2, 3, 4, 5]);
+
+        let input = "9007199254740992\n9007199254740993\n";
+        assert_eq!(
+            count_stream(input.as_bytes(), WindowConfig::PART_ONE).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn malformed_stream() {
+        let error = count_stream("199\r\n200\r\n2o8\r\n".as_bytes(), WindowConfig::PART_ONE);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "3:1: expected integer, found \"2o8\""
+        );
+    }
+}
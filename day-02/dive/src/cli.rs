@@ -0,0 +1,96 @@
+use crate::planner::{run_plan, Target};
+use crate::render::run_svg;
+use crate::submarine::{find_model, Arithmetic, Constraints, Model, Submarine, MODELS};
+use crate::trace::{run_replay, run_trace};
+use crate::{describe, model_answer, Dive};
+use aoc_common::{run_with, take_flag, take_option, Error, Result};
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// Modes of day 2 binaries.
+enum Mode {
+    Answer,
+    Trace,
+    Replay(PathBuf),
+    Plan(Target),
+    Svg,
+}
+
+// Main of both binaries, `default` is movement model of the part.
+// Command line arguments:
+//   [--format text|json] [--model direct|aim|3d] [--trace] [--strict]
+//   [--min-depth N] [--max-depth N] [--max-aim N] [--max-horizontal N] [file_name]...
+//   [--format text|json] --replay <trace_file> [--step N]
+//   [--format text|json] [--model direct|aim|3d] [limits] --plan <horizontal,depth>
+//   [--strict] [limits] --svg [--width N] [file_name]
+// Input is read from standard input when file name is `-` or not given.
+// With `--trace` state after every command is printed (CSV or JSON), both
+// traces can be replayed step by step with `--replay`. Limits are checked
+// after every command, `--strict` reports arithmetic overflow instead of
+// wrapping. `--plan` prints the shortest script reaching the target
+// position. `--svg` prints side view of the course with direct and aim
+// models.
+pub fn run(part: u8, default: &'static dyn Model) -> ExitCode {
+    let mut args = env::args().skip(1).collect::<Vec<String>>();
+    let (submarine, mode) = match take_options(&mut args, default) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match mode {
+        Mode::Replay(path) => run_replay(args, &path),
+        Mode::Trace => run_trace(args, submarine),
+        Mode::Plan(target) => run_plan(args, &submarine, target),
+        Mode::Svg => run_svg(args, submarine),
+        Mode::Answer => run_with::<Dive, _, _>(
+            args,
+            part,
+            |commands| model_answer(submarine.clone(), commands),
+            describe,
+        ),
+    }
+}
+
+fn take_options(
+    args: &mut Vec<String>,
+    default: &'static dyn Model,
+) -> Result<(Submarine<'static>, Mode)> {
+    let model = match take_option(args, "--model")? {
+        Some(name) => find_model(&name).ok_or_else(|| {
+            let names = MODELS.map(|model| model.name());
+            Error::Usage(format!(
+                "unknown model: '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            ))
+        })?,
+        None => default,
+    };
+    let modes = [
+        take_flag(args, "--trace").then_some(Mode::Trace),
+        take_option(args, "--replay")?.map(|path| Mode::Replay(PathBuf::from(path))),
+        match take_option(args, "--plan")? {
+            Some(target) => Some(Mode::Plan(Target::parse(&target)?)),
+            None => None,
+        },
+        take_flag(args, "--svg").then_some(Mode::Svg),
+    ];
+    let mut modes = modes.into_iter().flatten();
+    let mode = modes.next().unwrap_or(Mode::Answer);
+    if modes.next().is_some() {
+        return Err(Error::Usage(String::from(
+            "only one of '--trace', '--replay', '--plan' and '--svg' can be given",
+        )));
+    }
+    let arithmetic = match take_flag(args, "--strict") {
+        true => Arithmetic::Checked,
+        false => Arithmetic::Wrapping,
+    };
+    let submarine = Submarine::new(model)
+        .with_arithmetic(arithmetic)
+        .with_constraints(Constraints::take_from_args(args)?);
+    Ok((submarine, mode))
+}
@@ -0,0 +1,212 @@
+use aoc_common::{Answer, Error, Result, Solution};
+use std::fmt;
+use submarine::{Aim, Arithmetic, Direct, Model, Submarine, ThreeDimensional};
+
+pub mod cli;
+pub mod planner;
+pub mod render;
+pub mod script;
+pub mod submarine;
+pub mod trace;
+
+mod commands {
+    pub const FORWARD: &str = "forward";
+    pub const DOWN: &str = "down";
+    pub const UP: &str = "up";
+    pub const BACK: &str = "back";
+    pub const LEFT: &str = "left";
+    pub const RIGHT: &str = "right";
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keyword {
+    Forward,
+    Down,
+    Up,
+    Back,
+    // Turns by degrees, only models with heading support them.
+    Left,
+    Right,
+}
+
+impl Keyword {
+    pub const ALL: [Keyword; 6] = [
+        Keyword::Forward,
+        Keyword::Down,
+        Keyword::Up,
+        Keyword::Back,
+        Keyword::Left,
+        Keyword::Right,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keyword::Forward => commands::FORWARD,
+            Keyword::Down => commands::DOWN,
+            Keyword::Up => commands::UP,
+            Keyword::Back => commands::BACK,
+            Keyword::Left => commands::LEFT,
+            Keyword::Right => commands::RIGHT,
+        }
+    }
+}
+
+// Single command of a mission script with the line it comes from (commands
+// expanded from repeats and macros keep line of their definition).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Command {
+    pub keyword: Keyword,
+    pub amount: isize,
+    pub line: usize,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.keyword.name(), self.amount)
+    }
+}
+
+impl Command {
+    // Returns `(horizontal, vertical)` movement, `None` when negated amount
+    // overflows. Vertical movement changes depth (part one) or aim (part
+    // two). Turns don't move.
+    pub fn movement(&self, arithmetic: Arithmetic) -> Option<(isize, isize)> {
+        let (horizontal, vertical) = match self.keyword {
+            Keyword::Forward => (self.amount, 0),
+            Keyword::Back => (arithmetic.neg(self.amount)?, 0),
+            Keyword::Down => (0, self.amount),
+            Keyword::Up => (0, arithmetic.neg(self.amount)?),
+            Keyword::Left | Keyword::Right => (0, 0),
+        };
+        Some((horizontal, vertical))
+    }
+}
+
+pub struct Dive;
+
+impl Solution for Dive {
+    type Input = Vec<Command>;
+
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Dive!";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        script::parse_script(input)
+    }
+
+    fn part_one(commands: &Self::Input) -> Result<Answer> {
+        model_answer(Submarine::new(&Direct), commands)
+    }
+
+    fn part_two(commands: &Self::Input) -> Result<Answer> {
+        model_answer(Submarine::new(&Aim), commands)
+    }
+}
+
+pub fn model_answer(submarine: Submarine, commands: &[Command]) -> Result<Answer> {
+    let submarine = submarine.run(commands)?;
+    let (horizontal_position, depth) = submarine.position();
+    let product = submarine
+        .arithmetic()
+        .mul(horizontal_position, depth)
+        .ok_or_else(|| Error::solve("product of horizontal position and depth overflows"))?;
+    let answer = Answer::from(product)
+        .with("horizontal_position", horizontal_position)
+        .with("depth", depth)
+        .with("model", String::from(submarine.model().name()));
+    if submarine.model().name() != ThreeDimensional.name() {
+        return Ok(answer);
+    }
+    // Horizontal position is the length of the course, the position in
+    // the plane depends on turns.
+    let state = submarine.state();
+    Ok(answer
+        .with("x", state.x)
+        .with("y", state.y)
+        .with("heading", state.heading)
+        .with("distance", state.distance))
+}
+
+// Text printed by binaries.
+pub fn describe(answer: &Answer) -> String {
+    if answer.detail("x").is_some() {
+        return format!(
+            "x {:.3}, y {:.3}, depth {}, distance travelled {:.3}",
+            answer["x"], answer["y"], answer["depth"], answer["distance"]
+        );
+    }
+    format!(
+        "Horizontal position {}, depth {}, product {}",
+        answer["horizontal_position"], answer["depth"], answer
+    )
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{Aim, Direct, Model, Submarine, ThreeDimensional};
+    use aoc_common::Solution;
+
+    const EXAMPLE: &str = "forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2";
+
+    // Final `(horizontal position, depth)` of the example.
+    fn follow_course(model: &dyn Model) -> (isize, isize) {
+        let commands = super::Dive::parse(EXAMPLE).unwrap();
+        Submarine::new(model).run(&commands).unwrap().position()
+    }
+
+    #[test]
+    fn example_data() {
+        let (horizontal_position, depth) = follow_course(&Direct);
+        assert_eq!(horizontal_position * depth, 150);
+    }
+
+    #[test]
+    fn example_data_with_aim() {
+        let (horizontal_position, depth) = follow_course(&Aim);
+        assert_eq!(horizontal_position * depth, 900);
+    }
+
+    #[test]
+    fn malformed_command() {
+        let error = super::Dive::parse("forward 5\ndown 5\nforward 8a").unwrap_err();
+        assert_eq!(error.to_string(), "3:9: expected integer, found \"8a\"");
+
+        let error = super::Dive::parse("forward 5\n  sideways 5").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:3: expected command (forward, down, up, back, left, right, repeat, macro) or macro name, found \"sideways\""
+        );
+    }
+
+    #[test]
+    fn example_script() {
+        let script = "# the example\nforward 5\ndown 5\nforward 8\nUP 3\nrepeat 2 { down 4 }\nforward 3\nback 1";
+        let commands = super::Dive::parse(script).unwrap();
+        assert_eq!(super::Dive::part_one(&commands).unwrap().to_string(), "150");
+        assert_eq!(super::Dive::part_two(&commands).unwrap().to_string(), "900");
+    }
+
+    #[test]
+    fn three_dimensional_answer() {
+        let commands = super::Dive::parse("down 1\nforward 10\nleft 90\nforward 5").unwrap();
+        let answer = super::model_answer(Submarine::new(&ThreeDimensional), &commands).unwrap();
+        assert_eq!(answer.to_string(), "225");
+        assert_eq!(
+            super::describe(&answer),
+            "x 10.000, y 5.000, depth 15, distance travelled 21.213"
+        );
+        assert_eq!(answer["x"], aoc_common::Value::Float(10.0));
+        let distance = aoc_common::Json::from(&answer["distance"]);
+        assert_eq!(distance.to_string(), "21.213203435596427");
+        let error = super::model_answer(Submarine::new(&Aim), &commands).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: 'left 90' isn't supported by aim model"
+        );
+    }
+}
@@ -0,0 +1,381 @@
+// Inverse of the simulation: synthesize a shortest script of at most three
+// commands reaching target position. Candidate courses are ordered by length
+// and the first one that the simulator (with the given model and
+// constraints) confirms is chosen. Longer courses aren't searched, so under
+// tight limits there may be no course even though a longer one exists.
+//
+// With the direct model a target needs at most two commands. With aim the
+// depth changes only by `forward h` at aim `a` (by `h * a`), so:
+//   - `forward H` when target depth is 0,
+//   - `down D/H, forward H` when `H` divides `D`,
+//   - `forward H-h, down D/h, forward h` for a divisor `h` of `D` otherwise
+//     (three commands are needed, e.g. for `H` = 0 two commands can't change
+//     depth). Every other course of three commands reaches the same
+//     positions as a shorter one.
+// Depth after every command of these courses is either 0 or the target
+// depth, so depth limits don't need longer courses. Aim and horizontal
+// limits may rule out some divisors, so all of them are tried.
+use crate::submarine::{Arithmetic, Constraints, Model, Submarine};
+use crate::trace::exit_code;
+use crate::{Command, Keyword};
+use aoc_common::{Error, Json, OutputFormat, Result};
+use std::process::ExitCode;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Target {
+    pub horizontal: isize,
+    pub depth: isize,
+}
+
+impl Target {
+    // Parse `horizontal,depth`.
+    pub fn parse(text: &str) -> Result<Target> {
+        let usage = || {
+            Error::Usage(format!(
+                "incorrect target: '{}', expected horizontal,depth",
+                text
+            ))
+        };
+        let (horizontal, depth) = text.split_once(',').ok_or_else(usage)?;
+        Ok(Target {
+            horizontal: horizontal.trim().parse().map_err(|_| usage())?,
+            depth: depth.trim().parse().map_err(|_| usage())?,
+        })
+    }
+}
+
+// `forward`/`back` or `down`/`up` by signed amount, `None` for zero.
+fn command(horizontal: bool, amount: isize) -> Option<Command> {
+    let keyword = match (horizontal, amount >= 0) {
+        _ if amount == 0 => return None,
+        (true, true) => Keyword::Forward,
+        (true, false) => Keyword::Back,
+        (false, true) => Keyword::Down,
+        (false, false) => Keyword::Up,
+    };
+    Some(Command {
+        keyword,
+        amount: amount.checked_abs()?,
+        line: 0,
+    })
+}
+
+// Trial division stops here, see `divisors`.
+const MAX_TRIAL_DIVISOR: usize = 1 << 20;
+
+// Positive divisors of `value` in ascending order. Divisors are found in
+// pairs `d, value / d` with `d` up to `MAX_TRIAL_DIVISOR`, so for values
+// above 2^40 a pair of two larger divisors is missed.
+fn divisors(value: usize) -> Vec<usize> {
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let last = value.isqrt().min(MAX_TRIAL_DIVISOR);
+    for divisor in (1..=last).filter(|&divisor| value.is_multiple_of(divisor)) {
+        small.push(divisor);
+        if divisor != value / divisor {
+            large.push(value / divisor);
+        }
+    }
+    small.extend(large.iter().rev());
+    small
+}
+
+// Candidate courses, shorter first. Courses with amounts which can't be
+// expressed are skipped.
+fn candidates(target: Target) -> Vec<Vec<Command>> {
+    let Target { horizontal, depth } = target;
+    let mut courses = vec![
+        vec![command(true, horizontal), command(false, depth)],
+        vec![command(false, depth), command(true, horizontal)],
+    ];
+    // Last forward movement `h` at aim `depth / h`. Aim 1 and -1 first, then
+    // the remaining divisors with smaller moves first.
+    let mut last_moves = vec![depth, depth.saturating_neg(), 1, -1];
+    if horizontal != 0 && depth % horizontal == 0 {
+        last_moves.insert(0, horizontal);
+    }
+    for divisor in divisors(depth.unsigned_abs()) {
+        if let Ok(divisor) = isize::try_from(divisor) {
+            last_moves.extend([divisor, -divisor]);
+        }
+    }
+    for last in last_moves {
+        if last == 0 || depth % last != 0 {
+            continue;
+        }
+        if let Some(first) = horizontal.checked_sub(last) {
+            courses.push(vec![
+                command(true, first),
+                command(false, depth / last),
+                command(true, last),
+            ]);
+        }
+    }
+
+    let mut courses = courses
+        .into_iter()
+        .map(|course| course.into_iter().flatten().collect::<Vec<Command>>())
+        .collect::<Vec<_>>();
+    courses.sort_by_key(Vec::len);
+    courses.dedup();
+    courses
+}
+
+// Shortest course of at most three commands reaching `target` with `model`
+// under `constraints`.
+// Commands are numbered as lines of the script.
+pub fn plan(model: &dyn Model, target: Target, constraints: Constraints) -> Result<Vec<Command>> {
+    let reaches = |course: &[Command]| {
+        Submarine::new(model)
+            .with_arithmetic(Arithmetic::Checked)
+            .with_constraints(constraints)
+            .run(course)
+            .is_ok_and(|submarine| submarine.position() == (target.horizontal, target.depth))
+    };
+    let mut course = candidates(target)
+        .into_iter()
+        .find(|course| reaches(course))
+        .ok_or_else(|| {
+            Error::Solve(format!(
+                "no course of at most three commands to horizontal position {}, depth {} \
+                 with {} model and given limits",
+                target.horizontal,
+                target.depth,
+                model.name()
+            ))
+        })?;
+    for (i, command) in course.iter_mut().enumerate() {
+        command.line = i + 1;
+    }
+    Ok(course)
+}
+
+// Script in the puzzle input format (`back` and `up` for negative amounts).
+pub fn to_script(course: &[Command]) -> String {
+    course
+        .iter()
+        .map(|command| format!("{}\n", command))
+        .collect()
+}
+
+// Print course planned for the model and constraints of `submarine`, as a
+// script in text format or JSON object with list of commands.
+pub fn run_plan(mut args: Vec<String>, submarine: &Submarine, target: Target) -> ExitCode {
+    let result = OutputFormat::take_from_args(&mut args).and_then(|format| {
+        if !args.is_empty() {
+            return Err(Error::Usage(format!(
+                "unexpected arguments: {}",
+                args.join(" ")
+            )));
+        }
+        let course = plan(submarine.model(), target, submarine.constraints())?;
+        match format {
+            OutputFormat::Text => print!("{}", to_script(&course)),
+            OutputFormat::Json => {
+                let commands = course
+                    .iter()
+                    .map(Command::to_string)
+                    .collect::<Vec<String>>();
+                let record = Json::object()
+                    .field("model", submarine.model().name())
+                    .field("horizontal", target.horizontal as i64)
+                    .field("depth", target.depth as i64)
+                    .field("commands", commands);
+                println!("{}", record);
+            }
+        }
+        Ok(())
+    });
+    exit_code(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::parse_script;
+    use crate::submarine::{Aim, Direct};
+
+    // Plan, write the script, parse it and run it again.
+    fn round_trip(
+        model: &dyn Model,
+        horizontal: isize,
+        depth: isize,
+        constraints: Constraints,
+    ) -> usize {
+        let target = Target { horizontal, depth };
+        let course = plan(model, target, constraints).unwrap();
+        let commands = parse_script(&to_script(&course)).unwrap();
+        assert_eq!(commands, course);
+        let submarine = Submarine::new(model)
+            .with_constraints(constraints)
+            .run(&commands)
+            .unwrap();
+        assert_eq!(submarine.position(), (horizontal, depth));
+        commands.len()
+    }
+
+    #[test]
+    fn direct_courses() {
+        let none = Constraints::default();
+        assert_eq!(round_trip(&Direct, 0, 0, none), 0);
+        assert_eq!(round_trip(&Direct, 15, 10, none), 2);
+        assert_eq!(round_trip(&Direct, -4, 0, none), 1);
+        assert_eq!(round_trip(&Direct, 0, -7, none), 1);
+        assert_eq!(
+            to_script(
+                &plan(
+                    &Direct,
+                    Target {
+                        horizontal: -4,
+                        depth: 3
+                    },
+                    none
+                )
+                .unwrap()
+            ),
+            "back 4\ndown 3\n"
+        );
+    }
+
+    #[test]
+    fn aim_courses() {
+        let none = Constraints::default();
+        assert_eq!(round_trip(&Aim, 15, 60, none), 2);
+        assert_eq!(round_trip(&Aim, 15, 0, none), 1);
+        assert_eq!(round_trip(&Aim, 15, 61, none), 3);
+        assert_eq!(round_trip(&Aim, 0, 61, none), 3);
+        assert_eq!(round_trip(&Aim, -7, 61, none), 3);
+        assert_eq!(round_trip(&Aim, 1980, 991459, none), 3);
+        assert_eq!(round_trip(&Aim, 3, isize::MAX, none), 3);
+        assert_eq!(
+            to_script(
+                &plan(
+                    &Aim,
+                    Target {
+                        horizontal: 15,
+                        depth: 61
+                    },
+                    none
+                )
+                .unwrap()
+            ),
+            "back 46\ndown 1\nforward 61\n"
+        );
+    }
+
+    #[test]
+    fn depth_limits() {
+        let limits = Constraints {
+            min_depth: Some(5),
+            max_depth: Some(100),
+            ..Constraints::default()
+        };
+        // Vertical movement has to go first.
+        assert_eq!(
+            to_script(
+                &plan(
+                    &Direct,
+                    Target {
+                        horizontal: 8,
+                        depth: 50
+                    },
+                    limits
+                )
+                .unwrap()
+            ),
+            "down 50\nforward 8\n"
+        );
+        assert_eq!(round_trip(&Direct, 8, 50, limits), 2);
+        // With aim the first command never leaves the surface.
+        assert!(plan(
+            &Aim,
+            Target {
+                horizontal: 8,
+                depth: 50
+            },
+            limits
+        )
+        .is_err());
+        assert!(plan(
+            &Direct,
+            Target {
+                horizontal: 8,
+                depth: 150
+            },
+            limits
+        )
+        .is_err());
+
+        let surface = Constraints {
+            min_depth: Some(0),
+            ..Constraints::default()
+        };
+        assert_eq!(round_trip(&Aim, 8, 50, surface), 3);
+        let error = plan(
+            &Aim,
+            Target {
+                horizontal: 8,
+                depth: -50,
+            },
+            surface,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no course of at most three commands to horizontal position 8, depth -50 with aim model \
+             and given limits"
+        );
+    }
+
+    #[test]
+    fn aim_and_horizontal_limits() {
+        let limits = Constraints {
+            max_aim: Some(10),
+            max_horizontal: Some(10),
+            ..Constraints::default()
+        };
+        let target = Target {
+            horizontal: 2,
+            depth: 100,
+        };
+        assert_eq!(
+            to_script(&plan(&Aim, target, limits).unwrap()),
+            "back 8\ndown 10\nforward 10\n"
+        );
+        assert_eq!(round_trip(&Aim, 2, 100, limits), 3);
+        assert_eq!(round_trip(&Aim, -3, -70, limits), 3);
+        assert!(plan(
+            &Aim,
+            Target {
+                horizontal: 0,
+                depth: 101
+            },
+            limits
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn divisors_of_depth() {
+        assert_eq!(divisors(0), Vec::<usize>::new());
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(divisors(36), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(97), vec![1, 97]);
+        // 7^2 * 73 * 127 * 337 * 92737 * 649657
+        let divisors = divisors(isize::MAX as usize);
+        assert!(divisors.contains(&649657));
+        assert!(divisors.contains(&(isize::MAX as usize / 649657)));
+    }
+
+    #[test]
+    fn parse_target() {
+        assert_eq!(
+            Target::parse("15, -60").unwrap(),
+            Target {
+                horizontal: 15,
+                depth: -60
+            }
+        );
+        assert!(Target::parse("15").is_err());
+    }
+}
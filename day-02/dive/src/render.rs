@@ -0,0 +1,259 @@
+use crate::submarine::{Aim, Direct, Model, Submarine};
+use crate::trace::{exit_code, Trace};
+use crate::{Command, Dive};
+use aoc_common::{
+    svg_header, take_parsed_option, xml_escape, Error, InputSource, OutputFormat, Result, Solution,
+};
+use std::fmt::Write;
+use std::process::ExitCode;
+
+const MARGIN: f64 = 50.0;
+const PLOT_HEIGHT: f64 = 300.0;
+// Models overlaid on the chart with their line colours.
+const PROFILES: [(&dyn Model, &str); 2] = [(&Direct, "#1f77b4"), (&Aim, "#ff7f0e")];
+
+// Side view of a course: `(horizontal position, depth)` at the start and
+// after every command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub model: &'static str,
+    pub points: Vec<(isize, isize)>,
+    pub commands: Vec<Command>,
+}
+
+impl Profile {
+    // Course of `submarine` (its model, arithmetic and constraints).
+    pub fn record(submarine: Submarine, commands: &[Command]) -> Result<Self> {
+        let model = submarine.model().name();
+        let (trace, result) = Trace::record(submarine, commands);
+        result?;
+        let points = (0..=trace.len())
+            .filter_map(|step| trace.state(step))
+            .map(|state| (state.horizontal, state.depth))
+            .collect();
+        Ok(Profile {
+            model,
+            points,
+            commands: commands.to_vec(),
+        })
+    }
+
+    // Smallest and largest horizontal position and depth, the surface and
+    // start are always included.
+    fn bounds(profiles: &[Profile]) -> ((isize, isize), (isize, isize)) {
+        let points = profiles.iter().flat_map(|profile| &profile.points);
+        points.fold(
+            ((0, 0), (0, 0)),
+            |((left, right), (top, bottom)), &(x, y)| {
+                ((left.min(x), right.max(x)), (top.min(y), bottom.max(y)))
+            },
+        )
+    }
+}
+
+// Profiles of all overlaid models with the arithmetic and constraints of
+// `submarine`.
+pub fn profiles(submarine: &Submarine, commands: &[Command]) -> Result<Vec<Profile>> {
+    PROFILES
+        .iter()
+        .map(|(model, _)| {
+            let submarine = Submarine::new(*model)
+                .with_arithmetic(submarine.arithmetic())
+                .with_constraints(submarine.constraints());
+            Profile::record(submarine, commands)
+        })
+        .collect()
+}
+
+// Chart with depth growing downwards. Both axes are scaled separately (aim
+// courses are usually much deeper than long) and every point where a
+// command ends is marked, its tooltip is the command.
+pub fn svg(title: &str, profiles: &[Profile], width: usize) -> String {
+    let width = width.max(200) as f64;
+    let plot_width = width - 2.0 * MARGIN;
+    let height = PLOT_HEIGHT + 2.0 * MARGIN;
+    let ((left, right), (top, bottom)) = Profile::bounds(profiles);
+    // In floating point, the range of wrapping courses can exceed `isize`.
+    let scale = |value: isize, min: isize, max: isize, length: f64| {
+        if max > min {
+            (value as f64 - min as f64) / (max as f64 - min as f64) * length
+        } else {
+            0.0
+        }
+    };
+    let x = |horizontal: isize| MARGIN + scale(horizontal, left, right, plot_width);
+    let y = |depth: isize| MARGIN + scale(depth, top, bottom, PLOT_HEIGHT);
+
+    let mut svg = svg_header(title, width, height, MARGIN);
+    writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#999"/>"##,
+        MARGIN, MARGIN, plot_width, PLOT_HEIGHT
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r##"<line x1="{0}" y1="{2:.1}" x2="{1}" y2="{2:.1}" stroke="#999" stroke-dasharray="4"/>"##,
+        MARGIN,
+        MARGIN + plot_width,
+        y(0)
+    )
+    .unwrap();
+    for (text, tx, ty, anchor) in [
+        (top.to_string(), MARGIN - 4.0, MARGIN + 4.0, "end"),
+        (bottom.to_string(), MARGIN - 4.0, height - MARGIN, "end"),
+        (left.to_string(), MARGIN, height - MARGIN + 16.0, "start"),
+        (
+            right.to_string(),
+            width - MARGIN,
+            height - MARGIN + 16.0,
+            "end",
+        ),
+    ] {
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="{}">{}</text>"#,
+            tx, ty, anchor, text
+        )
+        .unwrap();
+    }
+
+    for (i, profile) in profiles.iter().enumerate() {
+        let colour = PROFILES
+            .iter()
+            .find(|(model, _)| model.name() == profile.model)
+            .map_or("#2ca02c", |(_, colour)| colour);
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+            width - MARGIN - 100.0,
+            20.0 + 14.0 * i as f64,
+            colour,
+            xml_escape(profile.model)
+        )
+        .unwrap();
+        let points = profile
+            .points
+            .iter()
+            .map(|&(horizontal, depth)| format!("{:.1},{:.1}", x(horizontal), y(depth)))
+            .collect::<Vec<String>>();
+        writeln!(
+            svg,
+            r#"<polyline fill="none" stroke="{}" stroke-width="1.5" points="{}"/>"#,
+            colour,
+            points.join(" ")
+        )
+        .unwrap();
+        writeln!(svg, r#"<g fill="{}">"#, colour).unwrap();
+        for (command, &(horizontal, depth)) in profile.commands.iter().zip(&profile.points[1..]) {
+            writeln!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="2"><title>line {}: {}</title></circle>"#,
+                x(horizontal),
+                y(depth),
+                command.line,
+                command
+            )
+            .unwrap();
+        }
+        writeln!(svg, "</g>").unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Print the chart of one input, remaining command line arguments:
+//   [--width N] [file_name]
+// The chart is `--width` pixels wide (800 by default).
+pub fn run_svg(mut args: Vec<String>, submarine: Submarine) -> ExitCode {
+    let width = OutputFormat::take_from_args(&mut args).and_then(|format| {
+        if format == OutputFormat::Json {
+            return Err(Error::Usage(String::from("SVG has no JSON output")));
+        }
+        take_parsed_option::<usize>(&mut args, "--width")
+    });
+    let result = width.and_then(|width| {
+        let sources = InputSource::from_args(&args);
+        if sources.len() != 1 {
+            return Err(Error::Usage(String::from("SVG needs exactly one input")));
+        }
+        let commands = Dive::parse_input(&sources[0])?;
+        let profiles =
+            profiles(&submarine, &commands).map_err(|e| e.with_path(sources[0].path()))?;
+        let title = format!("{}: {}", Dive::TITLE, sources[0]);
+        print!("{}", svg(&title, &profiles, width.unwrap_or(800)));
+        Ok(())
+    });
+    exit_code(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::parse_script;
+
+    const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+
+    #[test]
+    fn example_profiles() {
+        let commands = parse_script(EXAMPLE).unwrap();
+        let profiles = profiles(&Submarine::new(&Direct), &commands).unwrap();
+        let points = |i: usize| profiles[i].points.clone();
+        assert_eq!(
+            points(0),
+            vec![(0, 0), (5, 0), (5, 5), (13, 5), (13, 2), (13, 10), (15, 10)]
+        );
+        assert_eq!(
+            points(1),
+            vec![
+                (0, 0),
+                (5, 0),
+                (5, 0),
+                (13, 40),
+                (13, 40),
+                (13, 40),
+                (15, 60)
+            ]
+        );
+        assert_eq!(Profile::bounds(&profiles), ((0, 15), (0, 60)));
+    }
+
+    #[test]
+    fn example_svg() {
+        let commands = parse_script(EXAMPLE).unwrap();
+        let profiles = profiles(&Submarine::new(&Aim), &commands).unwrap();
+        let svg = svg("Dive <example>", &profiles, 350);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("Dive &lt;example&gt;"));
+        assert!(svg.contains(
+            r##"<polyline fill="none" stroke="#ff7f0e" stroke-width="1.5" points="50.0,50.0 133.3,50.0 133.3,50.0 266.7,250.0 266.7,250.0 266.7,250.0 300.0,350.0"/>"##
+        ));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 12);
+        assert!(svg.contains("<title>line 3: forward 8</title>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn wrapped_course() {
+        // Range of the course is wider than `isize`.
+        let commands = parse_script(&format!("down {}\nforward 2", isize::MAX)).unwrap();
+        let profiles = profiles(&Submarine::new(&Direct), &commands).unwrap();
+        assert_eq!(profiles[1].points[2], (2, isize::MAX.wrapping_mul(2)));
+        let svg = svg("Dive", &profiles, 400);
+        assert_eq!(svg.matches("<circle").count(), 4);
+    }
+
+    #[test]
+    fn constraint_breach() {
+        let commands = parse_script("up 1\nforward 1").unwrap();
+        let submarine = Submarine::new(&Aim).with_constraints(crate::submarine::Constraints {
+            min_depth: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(
+            profiles(&submarine, &commands).unwrap_err().to_string(),
+            "line 1: 'up 1' depth -1 breaches the surface"
+        );
+    }
+}
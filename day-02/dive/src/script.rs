@@ -0,0 +1,268 @@
+// Mission script language. Besides puzzle commands (`forward N`, `down N`,
+// `up N`) scripts may contain:
+//
+//   back 3                      # move backwards
+//   left 90                     # turn (degrees, only the 3D model)
+//   repeat 3 { forward 2 down 1 }
+//   macro zigzag { down 2 forward 1 up 2 }
+//   zigzag                      # expands to the macro body
+//
+// Keywords are case-insensitive, macro names are not. Everything after `#`
+// is a comment, line breaks are just whitespace. Macros must be defined
+// before they are used.
+use crate::{Command, Keyword};
+use aoc_common::{parse_integer, Error, Result};
+use std::collections::HashMap;
+
+const REPEAT: &str = "repeat";
+const MACRO: &str = "macro";
+
+// Limit of expanded commands, so nested repeats can't exhaust memory.
+pub const MAX_COMMANDS: usize = 10_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Token<'a> {
+    text: &'a str,
+    // Whole line for `column_of`-like error reporting.
+    line: &'a str,
+    line_number: usize,
+    column: usize,
+}
+
+impl Token<'_> {
+    fn error(&self, expected: &str) -> Error {
+        Error::parse(self.line_number, self.column, expected, self.text)
+    }
+
+    fn is(&self, keyword: &str) -> bool {
+        self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+// Split input into words and braces, comments are dropped.
+fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let code = line.split('#').next().unwrap_or_default();
+        let mut start = None;
+        for (offset, letter) in code.char_indices().chain([(code.len(), ' ')]) {
+            let separator = letter.is_whitespace() || letter == '{' || letter == '}';
+            if let (true, Some(begin)) = (separator, start) {
+                tokens.push(Token {
+                    text: &code[begin..offset],
+                    line,
+                    line_number: i + 1,
+                    column: begin + 1,
+                });
+                start = None;
+            }
+            if letter == '{' || letter == '}' {
+                tokens.push(Token {
+                    text: &code[offset..offset + 1],
+                    line,
+                    line_number: i + 1,
+                    column: offset + 1,
+                });
+            } else if !separator && start.is_none() {
+                start = Some(offset);
+            }
+        }
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    macros: HashMap<&'a str, Vec<Command>>,
+    // Position right after the last character of input.
+    end: (usize, usize),
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.position).copied();
+        self.position += 1;
+        token
+    }
+
+    // Next token or error at the end of input.
+    fn expect(&mut self, expected: &str) -> Result<Token<'a>> {
+        let (line, column) = self.end;
+        self.next()
+            .ok_or_else(|| Error::parse(line, column, expected, ""))
+    }
+
+    fn expect_open_brace(&mut self) -> Result<()> {
+        let token = self.expect("'{'")?;
+        if token.text != "{" {
+            return Err(token.error("'{'"));
+        }
+        Ok(())
+    }
+
+    // Commands until the closing brace (when `nested`) or end of input.
+    fn block(&mut self, nested: bool) -> Result<Vec<Command>> {
+        let mut commands = Vec::new();
+        loop {
+            let token = match self.next() {
+                Some(token) => token,
+                None if nested => return Err(self.expect("'}'").unwrap_err()),
+                None => return Ok(commands),
+            };
+            if token.text == "}" && nested {
+                return Ok(commands);
+            }
+
+            if let Some(keyword) = Keyword::ALL
+                .into_iter()
+                .find(|keyword| token.is(keyword.name()))
+            {
+                let amount = self.expect("integer")?;
+                commands.push(Command {
+                    keyword,
+                    amount: parse_integer(amount.line, amount.text, amount.line_number)?,
+                    line: token.line_number,
+                });
+            } else if token.is(REPEAT) {
+                let count = self.expect("repeat count")?;
+                let count = parse_integer::<usize>(count.line, count.text, count.line_number)?;
+                self.expect_open_brace()?;
+                let body = self.block(true)?;
+                extend(&mut commands, &body, count, &token)?;
+            } else if token.is(MACRO) {
+                let name = self.expect("macro name")?;
+                if !is_macro_name(name.text) || self.macros.contains_key(name.text) {
+                    return Err(name.error("new macro name"));
+                }
+                self.expect_open_brace()?;
+                let body = self.block(true)?;
+                self.macros.insert(name.text, body);
+            } else if let Some(body) = self.macros.get(token.text) {
+                let body = body.clone();
+                extend(&mut commands, &body, 1, &token)?;
+            } else {
+                let names = Keyword::ALL
+                    .iter()
+                    .map(|keyword| keyword.name())
+                    .chain([REPEAT, MACRO])
+                    .collect::<Vec<&str>>();
+                let expected = format!("command ({}) or macro name", names.join(", "));
+                return Err(token.error(&expected));
+            }
+        }
+    }
+}
+
+fn is_macro_name(name: &str) -> bool {
+    let reserved = Keyword::ALL
+        .iter()
+        .map(|keyword| keyword.name())
+        .chain([REPEAT, MACRO]);
+    name.starts_with(|letter: char| letter.is_alphabetic() || letter == '_')
+        && name
+            .chars()
+            .all(|letter| letter.is_alphanumeric() || letter == '_')
+        && !reserved
+            .into_iter()
+            .any(|keyword| name.eq_ignore_ascii_case(keyword))
+}
+
+// Append `count` copies of `body`, checking the expansion limit.
+fn extend(commands: &mut Vec<Command>, body: &[Command], count: usize, at: &Token) -> Result<()> {
+    let total = body
+        .len()
+        .checked_mul(count)
+        .and_then(|length| length.checked_add(commands.len()))
+        .filter(|&total| total <= MAX_COMMANDS);
+    if total.is_none() {
+        let expected = format!("at most {} commands after expansion", MAX_COMMANDS);
+        return Err(at.error(&expected));
+    }
+    // `cycle` of an empty body ends at once, whatever the count.
+    commands.extend(body.iter().cycle().take(body.len() * count).cloned());
+    Ok(())
+}
+
+// Parse script into list of commands with repeats and macros expanded.
+pub fn parse_script(input: &str) -> Result<Vec<Command>> {
+    let last_line = input.lines().count().max(1);
+    let last_column = input.lines().last().map_or(0, str::len) + 1;
+    let mut parser = Parser {
+        tokens: tokenize(input),
+        position: 0,
+        macros: HashMap::new(),
+        end: (last_line, last_column),
+    };
+    parser.block(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(input: &str) -> Vec<(Keyword, isize, usize)> {
+        parse_script(input)
+            .unwrap()
+            .iter()
+            .map(|command| (command.keyword, command.amount, command.line))
+            .collect()
+    }
+
+    #[test]
+    fn comments_case_and_back() {
+        let script = "# mission\n\nFORWARD 5   # cruise\n  Back 2\n\ndown\t3\n";
+        assert_eq!(
+            commands(script),
+            vec![
+                (Keyword::Forward, 5, 3),
+                (Keyword::Back, 2, 4),
+                (Keyword::Down, 3, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn repeat_and_macros() {
+        let script = "macro dip{down 1 up 1}\nrepeat 2 { forward 2\n dip }\nrepeat 0 { up 9 }";
+        assert_eq!(
+            commands(script),
+            vec![
+                (Keyword::Forward, 2, 2),
+                (Keyword::Down, 1, 1),
+                (Keyword::Up, 1, 1),
+                (Keyword::Forward, 2, 2),
+                (Keyword::Down, 1, 1),
+                (Keyword::Up, 1, 1),
+            ]
+        );
+        assert_eq!(commands("repeat 2 { repeat 3 { up 1 } }").len(), 6);
+        let empty = "macro nothing { }\nrepeat 100000000000 { }\nrepeat 100000000000 { nothing }";
+        assert_eq!(commands(empty), vec![]);
+    }
+
+    #[test]
+    fn malformed_script() {
+        let error = |script| parse_script(script).unwrap_err().to_string();
+        assert_eq!(error("forward"), "1:8: expected integer, found \"\"");
+        assert_eq!(
+            error("repeat 2 {\n up 1\n"),
+            "2:6: expected '}', found \"\""
+        );
+        assert_eq!(error("repeat 2 up 1"), "1:10: expected '{', found \"up\"");
+        assert_eq!(error("up 1 }"), "1:6: expected command (forward, down, up, back, left, right, repeat, macro) or macro name, found \"}\"");
+        assert_eq!(error("zig\nmacro zig { up 1 }"), "1:1: expected command (forward, down, up, back, left, right, repeat, macro) or macro name, found \"zig\"");
+        assert_eq!(
+            error("macro Up { up 1 }"),
+            "1:7: expected new macro name, found \"Up\""
+        );
+        assert_eq!(
+            error("macro a { }\nmacro a { }"),
+            "2:7: expected new macro name, found \"a\""
+        );
+        assert_eq!(
+            error("repeat 100000 { repeat 100000 { up 1 } }"),
+            "1:1: expected at most 10000000 commands after expansion, found \"repeat\""
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use crate::{Command, Keyword};
+use aoc_common::{take_parsed_option, Error, Result};
+
+// Position and aim of the submarine. Heading, plane coordinates and
+// travelled distance are tracked only by the 3D model, where `horizontal`
+// is the signed distance moved along the heading.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct State {
+    pub horizontal: isize,
+    pub depth: isize,
+    pub aim: isize,
+    // Degrees counterclockwise from the x axis, 0 to 359.
+    pub heading: isize,
+    pub x: f64,
+    pub y: f64,
+    pub distance: f64,
+}
+
+// Integer arithmetic used by models. Wrapping is the default (the same
+// result in debug and release builds), checked arithmetic reports overflow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    #[default]
+    Wrapping,
+    Checked,
+}
+
+impl Arithmetic {
+    pub fn add(self, a: isize, b: isize) -> Option<isize> {
+        match self {
+            Arithmetic::Wrapping => Some(a.wrapping_add(b)),
+            Arithmetic::Checked => a.checked_add(b),
+        }
+    }
+
+    pub fn mul(self, a: isize, b: isize) -> Option<isize> {
+        match self {
+            Arithmetic::Wrapping => Some(a.wrapping_mul(b)),
+            Arithmetic::Checked => a.checked_mul(b),
+        }
+    }
+
+    pub fn neg(self, a: isize) -> Option<isize> {
+        match self {
+            Arithmetic::Wrapping => Some(a.wrapping_neg()),
+            Arithmetic::Checked => a.checked_neg(),
+        }
+    }
+}
+
+// Movement model decides how commands change the state. New models are
+// added by implementing this trait (and listing them in `MODELS` to make
+// them selectable by name).
+pub trait Model {
+    fn name(&self) -> &'static str;
+
+    // Commands the model can't execute are rejected before `step`.
+    fn supports(&self, _keyword: Keyword) -> bool {
+        true
+    }
+
+    // Returns `None` on overflow.
+    fn step(&self, state: State, command: &Command, arithmetic: Arithmetic) -> Option<State>;
+}
+
+// Turns of models without heading.
+fn turn(keyword: Keyword) -> bool {
+    matches!(keyword, Keyword::Left | Keyword::Right)
+}
+
+// Vertical movement changes depth (part one).
+pub struct Direct;
+
+impl Model for Direct {
+    fn name(&self) -> &'static str {
+        "direct"
+    }
+
+    fn supports(&self, keyword: Keyword) -> bool {
+        !turn(keyword)
+    }
+
+    fn step(&self, state: State, command: &Command, arithmetic: Arithmetic) -> Option<State> {
+        let (horizontal, vertical) = command.movement(arithmetic)?;
+        Some(State {
+            horizontal: arithmetic.add(state.horizontal, horizontal)?,
+            depth: arithmetic.add(state.depth, vertical)?,
+            ..state
+        })
+    }
+}
+
+// Vertical movement changes aim, horizontal movement changes depth by aim
+// (part two).
+pub struct Aim;
+
+impl Model for Aim {
+    fn name(&self) -> &'static str {
+        "aim"
+    }
+
+    fn supports(&self, keyword: Keyword) -> bool {
+        !turn(keyword)
+    }
+
+    fn step(&self, state: State, command: &Command, arithmetic: Arithmetic) -> Option<State> {
+        let (horizontal, vertical) = command.movement(arithmetic)?;
+        let aim = arithmetic.add(state.aim, vertical)?;
+        let descent = arithmetic.mul(horizontal, aim)?;
+        Some(State {
+            horizontal: arithmetic.add(state.horizontal, horizontal)?,
+            depth: arithmetic.add(state.depth, descent)?,
+            aim,
+            ..state
+        })
+    }
+}
+
+// Aim model in three dimensions: `left` and `right` turn the heading by
+// degrees and `forward` moves along it. Travelled distance is the length
+// of the path including descent, i.e. `|h| * sqrt(1 + aim^2)` for every
+// `forward h`.
+pub struct ThreeDimensional;
+
+impl Model for ThreeDimensional {
+    fn name(&self) -> &'static str {
+        "3d"
+    }
+
+    fn step(&self, state: State, command: &Command, arithmetic: Arithmetic) -> Option<State> {
+        let turn = match command.keyword {
+            Keyword::Left => command.amount.rem_euclid(360),
+            Keyword::Right => 360 - command.amount.rem_euclid(360),
+            _ => 0,
+        };
+        let moved = command.movement(arithmetic)?.0 as f64;
+        let (cos, sin) = direction(state.heading);
+        let next = Aim.step(state, command, arithmetic)?;
+        Some(State {
+            heading: (state.heading + turn) % 360,
+            x: state.x + moved * cos,
+            y: state.y + moved * sin,
+            distance: state.distance + moved.abs() * (1.0 + (next.aim as f64).powi(2)).sqrt(),
+            ..next
+        })
+    }
+}
+
+// Unit vector of heading, exact for right angles.
+fn direction(heading: isize) -> (f64, f64) {
+    match heading {
+        0 => (1.0, 0.0),
+        90 => (0.0, 1.0),
+        180 => (-1.0, 0.0),
+        270 => (0.0, -1.0),
+        degrees => {
+            let radians = (degrees as f64).to_radians();
+            (radians.cos(), radians.sin())
+        }
+    }
+}
+
+pub const MODELS: [&dyn Model; 3] = [&Direct, &Aim, &ThreeDimensional];
+
+pub fn find_model(name: &str) -> Option<&'static dyn Model> {
+    MODELS.into_iter().find(|model| model.name() == name)
+}
+
+// Limits checked after every command. Aim and horizontal position are
+// limited in both directions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    pub min_depth: Option<isize>,
+    pub max_depth: Option<isize>,
+    pub max_aim: Option<isize>,
+    pub max_horizontal: Option<isize>,
+}
+
+impl Constraints {
+    // Take `--min-depth N`, `--max-depth N`, `--max-aim N` and
+    // `--max-horizontal N` options from arguments.
+    pub fn take_from_args(args: &mut Vec<String>) -> Result<Constraints> {
+        Ok(Constraints {
+            min_depth: take_parsed_option(args, "--min-depth")?,
+            max_depth: take_parsed_option(args, "--max-depth")?,
+            max_aim: take_parsed_option(args, "--max-aim")?,
+            max_horizontal: take_parsed_option(args, "--max-horizontal")?,
+        })
+    }
+
+    // Description of the first violated constraint.
+    pub fn check(&self, state: &State) -> Option<String> {
+        let State {
+            horizontal,
+            depth,
+            aim,
+            ..
+        } = *state;
+        match *self {
+            Constraints {
+                min_depth: Some(0), ..
+            } if depth < 0 => Some(format!("depth {} breaches the surface", depth)),
+            Constraints {
+                min_depth: Some(min),
+                ..
+            } if depth < min => Some(format!(
+                "depth {} is shallower than minimum depth {}",
+                depth, min
+            )),
+            Constraints {
+                max_depth: Some(max),
+                ..
+            } if depth > max => Some(format!("depth {} exceeds maximum depth {}", depth, max)),
+            Constraints {
+                max_aim: Some(max), ..
+            } if aim.unsigned_abs() > max.unsigned_abs() => {
+                Some(format!("aim {} exceeds maximum aim {}", aim, max))
+            }
+            Constraints {
+                max_horizontal: Some(max),
+                ..
+            } if horizontal.unsigned_abs() > max.unsigned_abs() => Some(format!(
+                "horizontal position {} exceeds maximum distance {}",
+                horizontal, max
+            )),
+            _ => None,
+        }
+    }
+}
+
+// Submarine state machine driven by commands.
+#[derive(Clone)]
+pub struct Submarine<'a> {
+    model: &'a dyn Model,
+    state: State,
+    arithmetic: Arithmetic,
+    constraints: Constraints,
+}
+
+impl<'a> Submarine<'a> {
+    // Submarine with wrapping arithmetic and no constraints.
+    pub fn new(model: &'a dyn Model) -> Self {
+        Submarine {
+            model,
+            state: State::default(),
+            arithmetic: Arithmetic::default(),
+            constraints: Constraints::default(),
+        }
+    }
+
+    pub fn with_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn model(&self) -> &dyn Model {
+        self.model
+    }
+
+    pub fn arithmetic(&self) -> Arithmetic {
+        self.arithmetic
+    }
+
+    pub fn constraints(&self) -> Constraints {
+        self.constraints
+    }
+
+    // Execute one command and return the new state. On unsupported command,
+    // overflow or violated constraint the state is not changed.
+    pub fn step(&mut self, command: &Command) -> Result<State> {
+        if !self.model.supports(command.keyword) {
+            return Err(Error::Usage(format!(
+                "line {}: '{}' isn't supported by {} model",
+                command.line,
+                command,
+                self.model.name()
+            )));
+        }
+        let violation = |problem: &str| {
+            Error::Solve(format!("line {}: '{}' {}", command.line, command, problem))
+        };
+        let state = self
+            .model
+            .step(self.state, command, self.arithmetic)
+            .ok_or_else(|| violation("overflows"))?;
+        if let Some(problem) = self.constraints.check(&state) {
+            return Err(violation(&problem));
+        }
+        self.state = state;
+        Ok(state)
+    }
+
+    pub fn run(mut self, commands: &[Command]) -> Result<Self> {
+        for command in commands {
+            self.step(command)?;
+        }
+        Ok(self)
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    // `(horizontal position, depth)`
+    pub fn position(&self) -> (isize, isize) {
+        (self.state.horizontal, self.state.depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::parse_script;
+
+    // Counts every command instead of moving.
+    struct Odometer;
+
+    impl Model for Odometer {
+        fn name(&self) -> &'static str {
+            "odometer"
+        }
+
+        fn step(&self, state: State, command: &Command, arithmetic: Arithmetic) -> Option<State> {
+            Some(State {
+                horizontal: arithmetic.add(state.horizontal, command.amount.abs())?,
+                ..state
+            })
+        }
+    }
+
+    fn run<'a>(model: &'a dyn Model, script: &str) -> Submarine<'a> {
+        Submarine::new(model)
+            .run(&parse_script(script).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn models() {
+        let script = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+        assert_eq!(run(&Direct, script).position(), (15, 10));
+        let submarine = run(&Aim, script);
+        assert_eq!(submarine.position(), (15, 60));
+        assert_eq!(submarine.state().aim, 10);
+        assert_eq!(run(&Odometer, script).position(), (31, 0));
+
+        assert_eq!(find_model("aim").map(|model| model.name()), Some("aim"));
+        assert!(find_model("odometer").is_none());
+    }
+
+    #[test]
+    fn unsupported_turns() {
+        let commands = parse_script("forward 5\n\nleft 90\nforward 5").unwrap();
+        for model in [&Direct as &dyn Model, &Aim] {
+            let error = Submarine::new(model).run(&commands).err().unwrap();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "line 3: 'left 90' isn't supported by {} model",
+                    model.name()
+                )
+            );
+        }
+        assert!(Submarine::new(&ThreeDimensional).run(&commands).is_ok());
+        assert!(Submarine::new(&Odometer).run(&commands).is_ok());
+    }
+
+    #[test]
+    fn step_by_step() {
+        let commands = parse_script("down 2\nforward 3\nback 1").unwrap();
+        let mut submarine = Submarine::new(&Aim);
+        let states = commands
+            .iter()
+            .map(|command| submarine.step(command).unwrap())
+            .collect::<Vec<State>>();
+        assert_eq!(
+            states,
+            vec![
+                State {
+                    horizontal: 0,
+                    depth: 0,
+                    aim: 2,
+                    ..State::default()
+                },
+                State {
+                    horizontal: 3,
+                    depth: 6,
+                    aim: 2,
+                    ..State::default()
+                },
+                State {
+                    horizontal: 2,
+                    depth: 4,
+                    aim: 2,
+                    ..State::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn constraints() {
+        let commands = parse_script("down 2\n# surface\nup 3\nforward 1").unwrap();
+        let surface = Constraints {
+            min_depth: Some(0),
+            ..Constraints::default()
+        };
+        let error = Submarine::new(&Direct)
+            .with_constraints(surface)
+            .run(&commands)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 3: 'up 3' depth -1 breaches the surface"
+        );
+
+        // With aim the submarine surfaces only when moving forward.
+        let submarine = Submarine::new(&Aim).with_constraints(surface);
+        let error = submarine.run(&commands).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 4: 'forward 1' depth -1 breaches the surface"
+        );
+
+        let limits = Constraints {
+            max_depth: Some(10),
+            max_aim: Some(1),
+            ..Constraints::default()
+        };
+        let submarine = Submarine::new(&Aim).with_constraints(limits);
+        let error = submarine.run(&commands).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 1: 'down 2' aim 2 exceeds maximum aim 1"
+        );
+
+        let mut args = ["--min-depth", "-5", "--max-horizontal", "100"]
+            .map(String::from)
+            .to_vec();
+        let constraints = Constraints::take_from_args(&mut args).unwrap();
+        assert_eq!(constraints.min_depth, Some(-5));
+        assert_eq!(constraints.max_horizontal, Some(100));
+    }
+
+    #[test]
+    fn three_dimensional() {
+        let script =
+            "forward 3\nleft 90\ndown 1\nforward 4\nright 450\nback 2\nright 45\nforward 2";
+        let submarine = run(&ThreeDimensional, script);
+        let state = submarine.state();
+        assert_eq!((state.horizontal, state.depth, state.aim), (7, 4, 1));
+        assert_eq!(state.heading, 315);
+        assert!((state.x - (3.0 - 2.0 + 2.0_f64.sqrt())).abs() < 1e-9);
+        assert!((state.y - (4.0 - 2.0_f64.sqrt())).abs() < 1e-9);
+        assert!((state.distance - (3.0 + 8.0 * 2.0_f64.sqrt())).abs() < 1e-9);
+
+        // Along a straight course it moves like the aim model.
+        let script = "forward 3\ndown 1\nforward 4\nback 2";
+        assert_eq!(run(&ThreeDimensional, script).position(), (5, 2));
+        assert_eq!(run(&Aim, script).position(), (5, 2));
+    }
+
+    #[test]
+    fn strict_arithmetic() {
+        let script = format!("down {}\nforward 2\nforward 2", isize::MAX / 3);
+        let commands = parse_script(&script).unwrap();
+        let submarine = Submarine::new(&Aim).run(&commands).unwrap();
+        assert_eq!(submarine.position(), (4, (isize::MAX / 3).wrapping_mul(4)));
+
+        let error = Submarine::new(&Aim)
+            .with_arithmetic(Arithmetic::Checked)
+            .run(&commands)
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "line 3: 'forward 2' overflows");
+    }
+}
@@ -0,0 +1,694 @@
+use crate::submarine::{Model, State, Submarine, ThreeDimensional};
+use crate::{Command, Dive, Keyword};
+use aoc_common::{
+    column_of, parse_integer, take_parsed_option, Error, InputSource, Json, OutputFormat, Result,
+    Solution,
+};
+use std::fmt::Write;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::process::ExitCode;
+
+const CSV_HEADER: &str = "step,line,command,horizontal,depth,aim";
+// Traces of the 3D model have columns of the state in the plane too.
+const CSV_HEADER_3D: &str = "step,line,command,horizontal,depth,aim,heading,x,y,distance";
+
+// State after one command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TraceStep {
+    pub command: Command,
+    pub state: State,
+}
+
+// States after every command of a script. Step 0 is the initial state,
+// step `n` is the state after `n`-th command.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+    // Recorded with the 3D model, heading, plane coordinates and distance
+    // are part of the trace.
+    pub three_dimensional: bool,
+}
+
+impl Trace {
+    // Record steps until the end of commands or the first error, which is
+    // returned together with the steps before it.
+    pub fn record(mut submarine: Submarine, commands: &[Command]) -> (Self, Result<()>) {
+        let mut trace = Trace {
+            steps: Vec::new(),
+            three_dimensional: submarine.model().name() == ThreeDimensional.name(),
+        };
+        for command in commands {
+            match submarine.step(command) {
+                Ok(state) => trace.steps.push(TraceStep {
+                    command: *command,
+                    state,
+                }),
+                Err(e) => return (trace, Err(e)),
+            }
+        }
+        (trace, Ok(()))
+    }
+
+    // Number of steps after the initial state.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn state(&self, step: usize) -> Option<State> {
+        match step {
+            0 => Some(State::default()),
+            step => self.steps.get(step - 1).map(|trace_step| trace_step.state),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(match self.three_dimensional {
+            true => CSV_HEADER_3D,
+            false => CSV_HEADER,
+        });
+        for (i, step) in self.steps.iter().enumerate() {
+            let state = step.state;
+            write!(
+                csv,
+                "\n{},{},{},{},{},{}",
+                i + 1,
+                step.command.line,
+                step.command,
+                state.horizontal,
+                state.depth,
+                state.aim
+            )
+            .unwrap();
+            // Shortest representation which parses back to the same `f64`.
+            if self.three_dimensional {
+                write!(
+                    csv,
+                    ",{},{},{},{}",
+                    state.heading, state.x, state.y, state.distance
+                )
+                .unwrap();
+            }
+        }
+        csv.push('\n');
+        csv
+    }
+
+    // Parse trace written by `to_csv` or JSON trace (one `step_json` object
+    // per line, without the initial state).
+    pub fn parse(input: &str) -> Result<Self> {
+        if input.starts_with('{') {
+            Trace::parse_json(input)
+        } else {
+            Trace::parse_csv(input)
+        }
+    }
+
+    // Parse trace written by `to_csv`.
+    pub fn parse_csv(input: &str) -> Result<Self> {
+        let mut lines = input.lines();
+        let three_dimensional = match lines.next() {
+            Some(CSV_HEADER) => false,
+            Some(CSV_HEADER_3D) => true,
+            header => return Err(Error::parse(1, 1, CSV_HEADER, header.unwrap_or_default())),
+        };
+        let steps = lines
+            .enumerate()
+            .map(|(i, line)| parse_csv_row(line, i + 2, i + 1, three_dimensional))
+            .collect::<Result<Vec<TraceStep>>>()?;
+        Ok(Trace {
+            steps,
+            three_dimensional,
+        })
+    }
+
+    // Parse JSON trace, the 3D model is recognized by heading of the first
+    // step.
+    pub fn parse_json(input: &str) -> Result<Self> {
+        let three_dimensional = input
+            .lines()
+            .next()
+            .map(|line| json_fields(line, 1))
+            .transpose()?
+            .is_some_and(|fields| fields.iter().any(|(name, _)| *name == "heading"));
+        let steps = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_json_row(line, i + 1, i + 1, three_dimensional))
+            .collect::<Result<Vec<TraceStep>>>()?;
+        Ok(Trace {
+            steps,
+            three_dimensional,
+        })
+    }
+
+    // JSON object describing step (0 is the initial state).
+    pub fn step_json(&self, step: usize) -> Json {
+        let record = Json::object().field("step", step);
+        let record = match step.checked_sub(1).and_then(|i| self.steps.get(i)) {
+            Some(trace_step) => record
+                .field("line", trace_step.command.line)
+                .field("command", trace_step.command.to_string()),
+            None => record
+                .field("line", None::<usize>)
+                .field("command", None::<String>),
+        };
+        let state = self.state(step).unwrap_or_default();
+        let record = record
+            .field("horizontal", state.horizontal as i64)
+            .field("depth", state.depth as i64)
+            .field("aim", state.aim as i64);
+        if !self.three_dimensional {
+            return record;
+        }
+        record
+            .field("heading", state.heading as i64)
+            .field("x", state.x)
+            .field("y", state.y)
+            .field("distance", state.distance)
+    }
+
+    // Human readable description of step.
+    pub fn describe_step(&self, step: usize) -> String {
+        let state = self.state(step).unwrap_or_default();
+        let command = match step.checked_sub(1).and_then(|i| self.steps.get(i)) {
+            Some(trace_step) => format!("line {}, {}", trace_step.command.line, trace_step.command),
+            None => String::from("start"),
+        };
+        let mut text = format!(
+            "step {}/{} ({}): horizontal {}, depth {}, aim {}",
+            step,
+            self.len(),
+            command,
+            state.horizontal,
+            state.depth,
+            state.aim
+        );
+        if self.three_dimensional {
+            write!(
+                text,
+                ", heading {}, x {:.3}, y {:.3}, distance {:.3}",
+                state.heading, state.x, state.y, state.distance
+            )
+            .unwrap();
+        }
+        text
+    }
+}
+
+fn parse_csv_row(
+    line: &str,
+    line_number: usize,
+    step: usize,
+    three_dimensional: bool,
+) -> Result<TraceStep> {
+    let fields = line.split(',').collect::<Vec<&str>>();
+    let expected = if three_dimensional { 10 } else { 6 };
+    if fields.len() != expected {
+        let expected = format!("{} comma separated fields", expected);
+        return Err(Error::parse(line_number, 1, &expected, line));
+    }
+    let number = parse_integer::<usize>(line, fields[0], line_number)?;
+    if number != step {
+        let expected = format!("step {}", step);
+        return Err(Error::parse(line_number, 1, &expected, fields[0]));
+    }
+    let mut state = State {
+        horizontal: parse_integer(line, fields[3], line_number)?,
+        depth: parse_integer(line, fields[4], line_number)?,
+        aim: parse_integer(line, fields[5], line_number)?,
+        ..State::default()
+    };
+    if three_dimensional {
+        state.heading = parse_integer(line, fields[6], line_number)?;
+        state.x = parse_float(line, fields[7], line_number)?;
+        state.y = parse_float(line, fields[8], line_number)?;
+        state.distance = parse_float(line, fields[9], line_number)?;
+    }
+    Ok(TraceStep {
+        command: parse_command(line, fields[2], fields[1], line_number)?,
+        state,
+    })
+}
+
+fn parse_json_row(
+    line: &str,
+    line_number: usize,
+    step: usize,
+    three_dimensional: bool,
+) -> Result<TraceStep> {
+    let fields = json_fields(line, line_number)?;
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(field_name, _)| *field_name == name)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| Error::parse(line_number, 1, &format!("field \"{}\"", name), line))
+    };
+    let token = field("step")?;
+    if parse_integer::<usize>(line, token, line_number)? != step {
+        let expected = format!("step {}", step);
+        return Err(Error::parse(
+            line_number,
+            column_of(line, token),
+            &expected,
+            token,
+        ));
+    }
+    let command = field("command")?;
+    let text = command
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .filter(|text| !text.contains('\\'))
+        .ok_or_else(|| Error::parse(line_number, column_of(line, command), "command", command))?;
+    let mut state = State {
+        horizontal: parse_integer(line, field("horizontal")?, line_number)?,
+        depth: parse_integer(line, field("depth")?, line_number)?,
+        aim: parse_integer(line, field("aim")?, line_number)?,
+        ..State::default()
+    };
+    if three_dimensional {
+        state.heading = parse_integer(line, field("heading")?, line_number)?;
+        state.x = parse_float(line, field("x")?, line_number)?;
+        state.y = parse_float(line, field("y")?, line_number)?;
+        state.distance = parse_float(line, field("distance")?, line_number)?;
+    }
+    Ok(TraceStep {
+        command: parse_command(line, text, field("line")?, line_number)?,
+        state,
+    })
+}
+
+// Command written as `keyword amount` on line `command_line` of the script,
+// both tokens are slices of `line`.
+fn parse_command(
+    line: &str,
+    command: &str,
+    command_line: &str,
+    line_number: usize,
+) -> Result<Command> {
+    let (keyword, amount) = match command.split_once(' ') {
+        Some(split) => split,
+        None => command.split_at(command.len()),
+    };
+    let keyword = Keyword::ALL
+        .into_iter()
+        .find(|known| known.name() == keyword)
+        .ok_or_else(|| Error::parse(line_number, column_of(line, command), "command", command))?;
+    Ok(Command {
+        keyword,
+        amount: parse_integer(line, amount, line_number)?,
+        line: parse_integer(line, command_line, line_number)?,
+    })
+}
+
+fn parse_float(line: &str, token: &str, line_number: usize) -> Result<f64> {
+    token
+        .parse::<f64>()
+        .map_err(|_| Error::parse(line_number, column_of(line, token), "number", token))
+}
+
+// Fields of flat JSON object on one line, as written by `step_json`. Values
+// are raw tokens (strings with their quotes), slices of `line`.
+fn json_fields(line: &str, line_number: usize) -> Result<Vec<(&str, &str)>> {
+    let bytes = line.as_bytes();
+    let skip_spaces = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        i
+    };
+    // End of string starting at `start` (after the closing quote).
+    let string_end = |start: usize| {
+        if bytes.get(start) != Some(&b'"') {
+            return None;
+        }
+        let mut i = start + 1;
+        while let Some(&byte) = bytes.get(i) {
+            match byte {
+                b'\\' => i += 2,
+                b'"' => return Some(i + 1),
+                _ => i += 1,
+            }
+        }
+        None
+    };
+    let error = |i: usize, expected: &str| {
+        Error::parse(
+            line_number,
+            i + 1,
+            expected,
+            line.get(i..).unwrap_or_default(),
+        )
+    };
+
+    let mut fields = Vec::new();
+    let mut i = skip_spaces(0);
+    if bytes.get(i) != Some(&b'{') {
+        return Err(error(i, "'{'"));
+    }
+    i = skip_spaces(i + 1);
+    if bytes.get(i) == Some(&b'}') {
+        i += 1;
+    } else {
+        loop {
+            let name_end = string_end(i).ok_or_else(|| error(i, "field name"))?;
+            let name = &line[i + 1..name_end - 1];
+            i = skip_spaces(name_end);
+            if bytes.get(i) != Some(&b':') {
+                return Err(error(i, "':'"));
+            }
+            i = skip_spaces(i + 1);
+            let value_end = match string_end(i) {
+                Some(end) => end,
+                None => line[i..]
+                    .find(|letter: char| letter == ',' || letter == '}' || letter.is_whitespace())
+                    .map_or(line.len(), |length| i + length),
+            };
+            if value_end == i {
+                return Err(error(i, "value"));
+            }
+            fields.push((name, &line[i..value_end]));
+            i = skip_spaces(value_end);
+            match bytes.get(i) {
+                Some(b',') => i = skip_spaces(i + 1),
+                Some(b'}') => {
+                    i += 1;
+                    break;
+                }
+                _ => return Err(error(i, "',' or '}'")),
+            }
+        }
+    }
+    i = skip_spaces(i);
+    if i < line.len() {
+        return Err(error(i, "end of line"));
+    }
+    Ok(fields)
+}
+
+// Cursor stepping forward and backward through a trace.
+pub struct Replay<'a> {
+    trace: &'a Trace,
+    step: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(trace: &'a Trace) -> Self {
+        Replay { trace, step: 0 }
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn state(&self) -> State {
+        self.trace.state(self.step).unwrap_or_default()
+    }
+
+    // Returns false when already at the last step.
+    pub fn forward(&mut self) -> bool {
+        self.seek(self.step + 1)
+    }
+
+    // Returns false when already at the initial state.
+    pub fn backward(&mut self) -> bool {
+        self.step > 0 && self.seek(self.step - 1)
+    }
+
+    // Returns false (and stays) when there is no such step.
+    pub fn seek(&mut self, step: usize) -> bool {
+        if step > self.trace.len() {
+            return false;
+        }
+        self.step = step;
+        true
+    }
+}
+
+// Print trace of one input, CSV in text format and one JSON object per
+// step in JSON format. On error steps before it are printed.
+pub fn run_trace(mut args: Vec<String>, submarine: Submarine) -> ExitCode {
+    let result = OutputFormat::take_from_args(&mut args).and_then(|format| {
+        let sources = InputSource::from_args(&args);
+        if sources.len() != 1 {
+            return Err(Error::Usage(String::from("trace needs exactly one input")));
+        }
+        let commands = Dive::parse_input(&sources[0])?;
+        let (trace, result) = Trace::record(submarine, &commands);
+        match format {
+            OutputFormat::Text => print!("{}", trace.to_csv()),
+            OutputFormat::Json => {
+                (1..=trace.len()).for_each(|step| println!("{}", trace.step_json(step)))
+            }
+        }
+        result.map_err(|e| e.with_path(sources[0].path()))
+    });
+    exit_code(result)
+}
+
+// Replay CSV or JSON trace. With `--step N` state at the step is printed,
+// otherwise
+// commands are read from standard input:
+//   n[ext], p[rev], g[oto] N, f[irst], l[ast], q[uit]
+pub fn run_replay(mut args: Vec<String>, path: &Path) -> ExitCode {
+    let result = OutputFormat::take_from_args(&mut args).and_then(|format| {
+        let step = take_parsed_option::<usize>(&mut args, "--step")?;
+        if !args.is_empty() {
+            return Err(Error::Usage(format!(
+                "unexpected arguments: {}",
+                args.join(" ")
+            )));
+        }
+        let source = InputSource::File(path.to_owned());
+        let trace = Trace::parse(&source.read()?).map_err(|e| e.with_path(path))?;
+        let print = |step: usize| match format {
+            OutputFormat::Text => println!("{}", trace.describe_step(step)),
+            OutputFormat::Json => println!("{}", trace.step_json(step)),
+        };
+
+        let mut replay = Replay::new(&trace);
+        if let Some(step) = step {
+            if !replay.seek(step) {
+                return Err(Error::Usage(format!(
+                    "no step {}, trace has {} steps",
+                    step,
+                    trace.len()
+                )));
+            }
+            print(step);
+            return Ok(());
+        }
+
+        print(replay.step());
+        for line in io::stdin().lock().lines() {
+            let line = line?;
+            let mut words = line.split_whitespace();
+            let moved = match (words.next(), words.next()) {
+                (Some("n" | "next"), None) => replay.forward(),
+                (Some("p" | "prev"), None) => replay.backward(),
+                (Some("f" | "first"), None) => replay.seek(0),
+                (Some("l" | "last"), None) => replay.seek(trace.len()),
+                (Some("g" | "goto"), Some(step)) => {
+                    step.parse().is_ok_and(|step| replay.seek(step))
+                }
+                (Some("q" | "quit"), None) => break,
+                (None, _) => continue,
+                _ => {
+                    eprintln!("commands: n[ext], p[rev], g[oto] N, f[irst], l[ast], q[uit]");
+                    continue;
+                }
+            };
+            if !moved {
+                eprintln!("no such step, trace has steps 0..={}", trace.len());
+            }
+            print(replay.step());
+        }
+        Ok(())
+    });
+    exit_code(result)
+}
+
+pub(crate) fn exit_code(result: Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::parse_script;
+    use crate::submarine::{Aim, Direct};
+
+    fn example_trace() -> Trace {
+        let commands =
+            parse_script("forward 5\ndown 5\n\nforward 8\nup 3\ndown 8\nforward 2").unwrap();
+        let (trace, result) = Trace::record(Submarine::new(&Aim), &commands);
+        assert!(result.is_ok());
+        trace
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let trace = example_trace();
+        let csv = trace.to_csv();
+        assert!(csv.starts_with(
+            "step,line,command,horizontal,depth,aim\n1,1,forward 5,5,0,0\n2,2,down 5,5,0,5\n3,4,forward 8,13,40,5\n"
+        ));
+        assert_eq!(Trace::parse_csv(&csv).unwrap(), trace);
+    }
+
+    #[test]
+    fn three_dimensional_round_trip() {
+        let commands = parse_script("down 1\nforward 10\nleft 30\nforward 5").unwrap();
+        let (trace, result) = Trace::record(Submarine::new(&ThreeDimensional), &commands);
+        assert!(result.is_ok());
+        let csv = trace.to_csv();
+        assert!(csv.starts_with(&format!(
+            "{}\n1,1,down 1,0,0,1,0,0,0,0\n2,2,forward 10,10,10,1,0,10,0,14.142135623730951\n",
+            CSV_HEADER_3D
+        )));
+        let parsed = Trace::parse_csv(&csv).unwrap();
+        assert_eq!(parsed, trace);
+        assert_eq!(
+            parsed.describe_step(4),
+            "step 4/4 (line 4, forward 5): horizontal 15, depth 15, aim 1, \
+             heading 30, x 14.330, y 2.500, distance 21.213"
+        );
+        assert_eq!(
+            parsed.step_json(3).to_string(),
+            r#"{"step":3,"line":3,"command":"left 30","horizontal":10,"depth":10,"aim":1,"heading":30,"x":10,"y":0,"distance":14.142135623730951}"#
+        );
+
+        let (trace, _) = Trace::record(Submarine::new(&Direct), &commands);
+        assert!(!trace.three_dimensional);
+        assert!(trace.to_csv().starts_with(&format!("{}\n", CSV_HEADER)));
+    }
+
+    #[test]
+    fn replay() {
+        let trace = example_trace();
+        let mut replay = Replay::new(&trace);
+        assert_eq!(replay.state(), State::default());
+        assert!(!replay.backward());
+        assert!(replay.forward() && replay.forward() && replay.forward());
+        assert_eq!(
+            replay.state(),
+            State {
+                horizontal: 13,
+                depth: 40,
+                aim: 5,
+                ..State::default()
+            }
+        );
+        assert!(replay.backward());
+        assert_eq!(replay.step(), 2);
+        assert!(replay.seek(6));
+        assert_eq!(
+            replay.state(),
+            State {
+                horizontal: 15,
+                depth: 60,
+                aim: 10,
+                ..State::default()
+            }
+        );
+        assert!(!replay.forward());
+        assert!(!replay.seek(7));
+        assert_eq!(
+            trace.describe_step(3),
+            "step 3/6 (line 4, forward 8): horizontal 13, depth 40, aim 5"
+        );
+        assert_eq!(
+            trace.step_json(0).to_string(),
+            r#"{"step":0,"line":null,"command":null,"horizontal":0,"depth":0,"aim":0}"#
+        );
+    }
+
+    #[test]
+    fn malformed_trace() {
+        let error = Trace::parse_csv("step,line\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1:1: expected step,line,command,horizontal,depth,aim, found \"step,line\""
+        );
+        let error = Trace::parse_csv(&format!(
+            "{}\n1,1,forward 5,5,0,0\n3,2,up 1,5,-1,0",
+            CSV_HEADER
+        ))
+        .unwrap_err();
+        assert_eq!(error.to_string(), "3:1: expected step 2, found \"3\"");
+        let error = Trace::parse_csv(&format!("{}\n1,1,sideways 5,5,0,0", CSV_HEADER)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:5: expected command, found \"sideways 5\""
+        );
+        let error =
+            Trace::parse_csv(&format!("{}\n1,1,left 5,0,0,0,5,0,0,x", CSV_HEADER_3D)).unwrap_err();
+        assert_eq!(error.to_string(), "2:24: expected number, found \"x\"");
+    }
+
+    fn to_json(trace: &Trace) -> String {
+        (1..=trace.len())
+            .map(|step| format!("{}\n", trace.step_json(step)))
+            .collect()
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let trace = example_trace();
+        assert_eq!(Trace::parse(&to_json(&trace)).unwrap(), trace);
+        assert_eq!(Trace::parse(&trace.to_csv()).unwrap(), trace);
+
+        let commands = parse_script("down 1\nforward 10\nleft 30\nforward 5\nback 3").unwrap();
+        let (trace, result) = Trace::record(Submarine::new(&ThreeDimensional), &commands);
+        assert!(result.is_ok());
+        let parsed = Trace::parse(&to_json(&trace)).unwrap();
+        assert!(parsed.three_dimensional);
+        assert_eq!(parsed, trace);
+
+        let line =
+            r#"{ "step" : 1, "line":3,"command":"up 2" ,"horizontal":0,"depth":-2,"aim":0 }"#;
+        let parsed = Trace::parse(line).unwrap();
+        assert_eq!(parsed.steps[0].command.line, 3);
+        assert_eq!(parsed.state(1).map(|state| state.depth), Some(-2));
+    }
+
+    #[test]
+    fn malformed_json_trace() {
+        let error = |trace: &str| Trace::parse(trace).unwrap_err().to_string();
+        let first = r#"{"step":1,"line":1,"command":"forward 5","horizontal":5,"depth":0,"aim":0}"#;
+        assert_eq!(
+            error(&format!("{}\n{}", first, first)),
+            "2:9: expected step 2, found \"1\""
+        );
+        assert_eq!(
+            error(r#"{"step":1,"line":1,"command":"forward 5","depth":0,"aim":0}"#),
+            format!(
+                "1:1: expected field \"horizontal\", found {:?}",
+                r#"{"step":1,"line":1,"command":"forward 5","depth":0,"aim":0}"#
+            )
+        );
+        assert_eq!(
+            error(r#"{"step":1,"line":1,"command":"dive 5","horizontal":5,"depth":0,"aim":0}"#),
+            "1:31: expected command, found \"dive 5\""
+        );
+        assert_eq!(
+            error(r#"{"step":1 "line":1}"#),
+            "1:11: expected ',' or '}', found \"\\\"line\\\":1}\""
+        );
+        assert_eq!(
+            error(r#"{"step":1} x"#),
+            "1:12: expected end of line, found \"x\""
+        );
+    }
+}
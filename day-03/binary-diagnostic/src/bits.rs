@@ -0,0 +1,262 @@
+use aoc_common::{Error, Value};
+use std::fmt;
+use std::str::FromStr;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// Number of words needed for `width` bits, at least one.
+pub fn words_for(width: usize) -> usize {
+    width.div_ceil(WORD_BITS).max(1)
+}
+
+// Position of the bit in `column` (counted from the left) of a `width` bits
+// wide number stored as little-endian words. Returns `(word, mask)`.
+pub fn position(width: usize, column: usize) -> (usize, u64) {
+    let bit = width - 1 - column;
+    (bit / WORD_BITS, 1 << (bit % WORD_BITS))
+}
+
+// Unsigned number of any width, e.g. a gamma rate of a very wide report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitVector {
+    width: usize,
+    // Little-endian, bits above `width` are zero.
+    words: Vec<u64>,
+}
+
+impl BitVector {
+    pub fn zero(width: usize) -> Self {
+        BitVector {
+            width,
+            words: vec![0; words_for(width)],
+        }
+    }
+
+    pub fn from_words(width: usize, words: &[u64]) -> Self {
+        let mut vector = BitVector::zero(width);
+        vector.words.copy_from_slice(words);
+        vector.clear_unused();
+        vector
+    }
+
+    // Binary digits, the first one is the most significant.
+    pub fn parse(digits: &str) -> Option<Self> {
+        let mut vector = BitVector::zero(digits.len());
+        for (column, digit) in digits.chars().enumerate() {
+            match digit {
+                '0' => (),
+                '1' => vector.set(column),
+                _ => return None,
+            }
+        }
+        Some(vector)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn bit(&self, column: usize) -> bool {
+        let (word, mask) = position(self.width, column);
+        self.words[word] & mask != 0
+    }
+
+    pub fn set(&mut self, column: usize) {
+        let (word, mask) = position(self.width, column);
+        self.words[word] |= mask;
+    }
+
+    // All `width` bits inverted.
+    pub fn not(&self) -> Self {
+        let mut vector = BitVector {
+            width: self.width,
+            words: self.words.iter().map(|word| !word).collect(),
+        };
+        vector.clear_unused();
+        vector
+    }
+
+    fn clear_unused(&mut self) {
+        let used = self.width % WORD_BITS;
+        if used != 0 {
+            *self.words.last_mut().unwrap() &= (1 << used) - 1;
+        }
+        if self.width == 0 {
+            self.words[0] = 0;
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.words.iter().skip(2).any(|&word| word != 0) {
+            return None;
+        }
+        let high = self.words.get(1).copied().unwrap_or_default();
+        Some(u128::from(high) << 64 | u128::from(self.words[0]))
+    }
+
+    // Exact product, `width` is the sum of both widths.
+    pub fn mul(&self, other: &BitVector) -> BitVector {
+        let mut product = BitVector::zero(self.width + other.width);
+        for (i, &a) in self.words.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.words.iter().enumerate() {
+                let word = match product.words.get_mut(i + j) {
+                    Some(word) => word,
+                    // Remaining words of the product are zero.
+                    None => break,
+                };
+                let sum = u128::from(a) * u128::from(b) + u128::from(*word) + carry;
+                *word = sum as u64;
+                carry = sum >> 64;
+            }
+            if let Some(word) = product.words.get_mut(i + other.words.len()) {
+                *word = carry as u64;
+            }
+        }
+        product
+    }
+
+    pub fn to_binary(&self) -> String {
+        (0..self.width)
+            .map(|column| if self.bit(column) { '1' } else { '0' })
+            .collect()
+    }
+
+    // Lowercase digits, enough for all `width` bits.
+    pub fn to_hex(&self) -> String {
+        let digits = self.width.div_ceil(4);
+        (0..digits)
+            .rev()
+            .map(|digit| {
+                let bit = digit * 4;
+                let word = self.words[bit / WORD_BITS] >> (bit % WORD_BITS);
+                char::from_digit((word & 0xf) as u32, 16).unwrap()
+            })
+            .collect()
+    }
+
+    pub fn to_decimal(&self) -> String {
+        if let Some(value) = self.to_u128() {
+            return value.to_string();
+        }
+        // Repeated division by the largest power of ten fitting in a word.
+        const DIVISOR: u64 = 10_000_000_000_000_000_000;
+        let mut words = self.words.clone();
+        let mut chunks = Vec::new();
+        while words.iter().any(|&word| word != 0) {
+            let mut remainder = 0u128;
+            for word in words.iter_mut().rev() {
+                let value = remainder << 64 | u128::from(*word);
+                *word = (value / u128::from(DIVISOR)) as u64;
+                remainder = value % u128::from(DIVISOR);
+            }
+            chunks.push(remainder as u64);
+        }
+        let mut decimal = chunks.pop().unwrap_or_default().to_string();
+        for chunk in chunks.iter().rev() {
+            decimal.push_str(&format!("{:019}", chunk));
+        }
+        decimal
+    }
+}
+
+// Decimal digits.
+impl fmt::Display for BitVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_decimal())
+    }
+}
+
+// Integer when the number fits, decimal text otherwise.
+impl From<&BitVector> for Value {
+    fn from(vector: &BitVector) -> Self {
+        match vector.to_u128().and_then(|value| i64::try_from(value).ok()) {
+            Some(value) => Value::Integer(value),
+            None => Value::Text(vector.to_decimal()),
+        }
+    }
+}
+
+// Radix in which binaries print numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Radix {
+    Binary,
+    Hex,
+    #[default]
+    Decimal,
+}
+
+impl Radix {
+    pub fn format(self, vector: &BitVector) -> String {
+        match self {
+            Radix::Binary => vector.to_binary(),
+            Radix::Hex => vector.to_hex(),
+            Radix::Decimal => vector.to_decimal(),
+        }
+    }
+}
+
+impl FromStr for Radix {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "binary" => Ok(Radix::Binary),
+            "hex" => Ok(Radix::Hex),
+            "decimal" => Ok(Radix::Decimal),
+            name => Err(Error::Usage(format!(
+                "unknown radix: '{}', expected binary, hex or decimal",
+                name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radixes() {
+        let vector = BitVector::parse("010110").unwrap();
+        assert_eq!(vector.to_binary(), "010110");
+        assert_eq!(vector.to_hex(), "16");
+        assert_eq!(vector.to_decimal(), "22");
+        assert_eq!(vector.not().to_binary(), "101001");
+        assert!(vector.bit(1) && !vector.bit(2));
+        assert_eq!(BitVector::parse("").unwrap().to_decimal(), "0");
+        assert!(BitVector::parse("012").is_none());
+    }
+
+    #[test]
+    fn wide_numbers() {
+        let ones = BitVector::parse(&"1".repeat(130)).unwrap();
+        assert_eq!(ones.to_u128(), None);
+        assert_eq!(ones.to_hex(), format!("3{}", "f".repeat(32)));
+        assert_eq!(ones.not(), BitVector::zero(130));
+        // 2^130 - 1
+        assert_eq!(
+            ones.to_decimal(),
+            "1361129467683753853853498429727072845823"
+        );
+
+        let max = BitVector::parse(&"1".repeat(128)).unwrap();
+        assert_eq!(max.to_u128(), Some(u128::MAX));
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        let square = max.mul(&max);
+        assert_eq!(square.width(), 256);
+        assert_eq!(
+            square.to_binary(),
+            format!("{}0{}1", "1".repeat(127), "0".repeat(127))
+        );
+        assert_eq!(
+            square.to_decimal(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(
+            Value::from(&BitVector::parse("10110").unwrap()),
+            Value::Integer(22)
+        );
+        assert_eq!(Value::from(&square), Value::Text(square.to_decimal()));
+    }
+}
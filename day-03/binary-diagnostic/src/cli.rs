@@ -0,0 +1,155 @@
+use crate::bits::Radix;
+use crate::counting::{count_parallel, count_reader};
+use crate::explain::Explanation;
+use crate::index::{Rating, RatingIndex};
+use crate::{
+    counts_answer, format_detail, life_support_answer, ratings_answer, BinaryDiagnostic, TieBreak,
+};
+use aoc_common::{
+    run_sources, run_with, take_flag, take_parsed_option, Answer, Error, InputSource, OutputFormat,
+    Result, Solution,
+};
+use std::env;
+use std::process::ExitCode;
+
+struct Options {
+    radix: Radix,
+    tie_break: TieBreak,
+    threads: Option<usize>,
+    // Limit of listed candidates when explaining ratings.
+    explain: Option<usize>,
+}
+
+// Main of both binaries. Command line arguments:
+//   [--format text|json] [--radix binary|hex|decimal]
+//   [--tie-break one|zero|error|both] [--threads N] [file_name]...
+//   [--format text|json] [--tie-break ...] --explain [--candidates N] [file_name]...
+// Part one counts columns in one pass over the input, by `--threads`
+// threads when given (the whole input is read to memory then). Part two
+// with `--explain` prints every filtered column of both ratings before the
+// answer, remaining lines are listed once at most `--candidates` are left.
+pub fn run(part: u8) -> ExitCode {
+    let mut args = env::args().skip(1).collect::<Vec<String>>();
+    let options = match take_options(&mut args, part) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Options {
+        radix,
+        tie_break,
+        threads,
+        explain,
+    } = options;
+    let describe = |answer: &Answer| describe(answer, part, radix);
+    match (part, explain) {
+        (1, _) => run_sources::<BinaryDiagnostic, _, _>(
+            args,
+            part,
+            |source, _| {
+                let counts = match threads {
+                    Some(threads) => source
+                        .read()
+                        .and_then(|input| count_parallel(&input, threads)),
+                    None => source.reader().and_then(count_reader),
+                };
+                counts
+                    .map_err(|e| e.with_path(source.path()))
+                    .and_then(|counts| counts_answer(&counts, tie_break))
+            },
+            describe,
+        ),
+        (_, Some(candidates)) => run_sources::<BinaryDiagnostic, _, _>(
+            args,
+            part,
+            |source, format| explain_source(source, format, tie_break, candidates),
+            describe,
+        ),
+        (_, None) => run_with::<BinaryDiagnostic, _, _>(
+            args,
+            part,
+            |report| life_support_answer(report, tie_break),
+            describe,
+        ),
+    }
+}
+
+fn take_options(args: &mut Vec<String>, part: u8) -> Result<Options> {
+    let radix = take_parsed_option::<Radix>(args, "--radix")?;
+    let tie_break = take_parsed_option::<TieBreak>(args, "--tie-break")?;
+    let threads = take_parsed_option::<usize>(args, "--threads")?;
+    let explain = take_flag(args, "--explain");
+    let candidates = take_parsed_option::<usize>(args, "--candidates")?;
+    let only = |option: &str, part: &str| {
+        Err(Error::Usage(format!(
+            "'{}' is supported by part {} only",
+            option, part
+        )))
+    };
+    match threads {
+        Some(0) => return Err(Error::Usage(String::from("'--threads' must be positive"))),
+        Some(_) if part != 1 => return only("--threads", "one"),
+        _ => (),
+    }
+    if explain && part == 1 {
+        return only("--explain", "two");
+    }
+    if candidates.is_some() && !explain {
+        return Err(Error::Usage(String::from(
+            "'--candidates' requires '--explain'",
+        )));
+    }
+    Ok(Options {
+        radix: radix.unwrap_or_default(),
+        tie_break: tie_break.unwrap_or_default(),
+        threads,
+        explain: explain.then(|| candidates.unwrap_or(0)),
+    })
+}
+
+// Print explanations of both ratings, returns the answer of part two.
+fn explain_source(
+    source: &InputSource,
+    format: OutputFormat,
+    tie_break: TieBreak,
+    candidates: usize,
+) -> Result<Answer> {
+    let report = BinaryDiagnostic::parse_input(source)?;
+    let index = RatingIndex::new(&report);
+    let mut ratings = Vec::with_capacity(2);
+    for rating in [Rating::OxygenGenerator, Rating::Co2Scrubber] {
+        let (explanation, result) = Explanation::record(&index, rating, tie_break, candidates);
+        match format {
+            OutputFormat::Text => print!("{}", explanation),
+            OutputFormat::Json => {
+                for step in &explanation.steps {
+                    println!("{}", explanation.step_json(step));
+                }
+                println!("{}", explanation.survivor_json());
+            }
+        }
+        ratings.push(result.map_err(|e| e.with_path(source.path()))?);
+    }
+    Ok(ratings_answer(&ratings[0], &ratings[1], tie_break))
+}
+
+// Text printed by binaries.
+pub fn describe(answer: &Answer, part: u8, radix: Radix) -> String {
+    let detail = |name| format_detail(answer, name, radix);
+    match part {
+        1 => format!(
+            "Gamma rate: {}, epsilon rate {}, product {}",
+            detail("gamma_rate"),
+            detail("epsilon_rate"),
+            detail("product")
+        ),
+        _ => format!(
+            "Oxygen generator rating: {}\nCO2 scrubber rating: {}\nLife support rating: {}",
+            detail("oxygen_generator_rating"),
+            detail("co2_scrubber_rating"),
+            detail("product")
+        ),
+    }
+}
@@ -0,0 +1,212 @@
+use crate::DiagnosticReport;
+use aoc_common::{Error, Result};
+use std::io::BufRead;
+use std::thread;
+
+// Number of lines and of ones in every column, which is all the gamma and
+// epsilon rates need.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnCounts {
+    pub lines: usize,
+    pub ones: Vec<usize>,
+}
+
+impl ColumnCounts {
+    pub fn with_width(width: usize) -> Self {
+        ColumnCounts {
+            lines: 0,
+            ones: vec![0; width],
+        }
+    }
+
+    pub fn from_report(report: &DiagnosticReport) -> Self {
+        ColumnCounts {
+            lines: report.statistic.lines,
+            ones: report.column_counts(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.ones.len()
+    }
+
+    // Add counts of the following part of the same report.
+    pub fn merge(&mut self, other: &ColumnCounts) {
+        self.lines += other.lines;
+        for (ones, other) in self.ones.iter_mut().zip(&other.ones) {
+            *ones += other;
+        }
+    }
+
+    // Count one line. Errors are the same as of `BinaryDiagnostic::parse`,
+    // but found in one pass, so the first malformed line is reported
+    // whether it has wrong width or digit.
+    pub fn push(&mut self, line: &str, line_number: usize) -> Result<()> {
+        let width = self.width();
+        let valid = |letter: &u8| *letter == b'0' || *letter == b'1';
+        // Only valid lines have one byte per column.
+        if line.len() == width && line.as_bytes().iter().all(valid) {
+            for (ones, &letter) in self.ones.iter_mut().zip(line.as_bytes()) {
+                *ones += usize::from(letter == b'1');
+            }
+            self.lines += 1;
+            return Ok(());
+        }
+        if line.chars().count() != width {
+            let expected = format!("{} binary digits", width);
+            return Err(Error::parse(line_number, 1, &expected, line));
+        }
+        let (column, letter) = line
+            .chars()
+            .enumerate()
+            .find(|(_, letter)| *letter != '0' && *letter != '1')
+            .expect("line with wrong digit");
+        Err(Error::parse(
+            line_number,
+            column + 1,
+            "binary digit",
+            &letter.to_string(),
+        ))
+    }
+}
+
+// Count report in one pass over the reader, only the current line is kept
+// in memory.
+pub fn count_reader<R: BufRead>(mut reader: R) -> Result<ColumnCounts> {
+    let mut counts = ColumnCounts::default();
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(counts);
+        }
+        line_number += 1;
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line_number == 1 {
+            // Width is set by the first line.
+            counts = ColumnCounts::with_width(line.chars().count());
+        }
+        counts.push(line, line_number)?;
+    }
+}
+
+// Same counts as `count_reader`, but chunks of the input are counted by
+// `threads` threads. Every chunk starts at the beginning of a line, so the
+// per-column counts can simply be added. There are never more threads than
+// lines or than the machine can run in parallel.
+pub fn count_parallel(input: &str, threads: usize) -> Result<ColumnCounts> {
+    let empty = match input.lines().next() {
+        Some(first) => ColumnCounts::with_width(first.chars().count()),
+        None => ColumnCounts::default(),
+    };
+    let available = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let threads = threads.min(available).min(input.lines().count()).max(1);
+
+    // `(first line number, chunk)`, at most `threads` chunks of whole lines.
+    let mut chunks = Vec::new();
+    let mut rest = input;
+    let mut line_number = 1;
+    let size = input.len() / threads + 1;
+    while !rest.is_empty() {
+        let after = rest.as_bytes().get(size..).unwrap_or_default();
+        let end = match after.iter().position(|&byte| byte == b'\n') {
+            Some(newline) => size + newline + 1,
+            None => rest.len(),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push((line_number, chunk));
+        line_number += chunk.bytes().filter(|&byte| byte == b'\n').count();
+        rest = tail;
+    }
+
+    let results = thread::scope(|scope| {
+        let handles = chunks
+            .iter()
+            .map(|&(first_line, chunk)| {
+                let mut counts = empty.clone();
+                thread::Builder::new()
+                    .spawn_scoped(scope, move || {
+                        for (i, line) in chunk.lines().enumerate() {
+                            counts.push(line, first_line + i)?;
+                        }
+                        Ok(counts)
+                    })
+                    .map_err(|e| Error::Solve(format!("unable to start counting thread: {}", e)))
+            })
+            .collect::<Vec<_>>();
+        // Threads which did start are joined by the scope in any case.
+        handles
+            .into_iter()
+            .map(|handle| handle?.join().expect("counting thread panicked"))
+            .collect::<Vec<Result<ColumnCounts>>>()
+    });
+    // The first error in input order, as reported by `count_reader`.
+    let mut total = empty;
+    for counts in results {
+        total.merge(&counts?);
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BinaryDiagnostic;
+    use aoc_common::Solution;
+
+    const PUZZLE_INPUT: &str = include_str!("../data/puzzle_input.dat");
+
+    #[test]
+    fn same_counts_as_report() {
+        let report = BinaryDiagnostic::parse(PUZZLE_INPUT).unwrap();
+        let expected = ColumnCounts::from_report(&report);
+        assert_eq!(expected.lines, 1000);
+        assert_eq!(count_reader(PUZZLE_INPUT.as_bytes()).unwrap(), expected);
+        for threads in [1, 2, 3, 8, 2000, usize::MAX] {
+            assert_eq!(count_parallel(PUZZLE_INPUT, threads).unwrap(), expected);
+        }
+        let crlf = PUZZLE_INPUT.replace('\n', "\r\n");
+        assert_eq!(count_reader(crlf.as_bytes()).unwrap(), expected);
+        assert_eq!(count_parallel(&crlf, 4).unwrap(), expected);
+
+        assert_eq!(
+            count_reader("".as_bytes()).unwrap(),
+            ColumnCounts::default()
+        );
+        assert_eq!(count_parallel("", 4).unwrap(), ColumnCounts::default());
+    }
+
+    #[test]
+    fn more_threads_than_lines() {
+        let input = "00100\n11110\n10110";
+        let expected = count_reader(input.as_bytes()).unwrap();
+        for threads in [3, 4, 200000] {
+            assert_eq!(count_parallel(input, threads).unwrap(), expected);
+        }
+        let error = count_parallel("0\n1\n2", 200000).unwrap_err();
+        assert_eq!(error.to_string(), "3:1: expected binary digit, found \"2\"");
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let input = "00100\n11110\n10110\n10111\n10120\n1011\n";
+        for threads in [1, 2, 6] {
+            let error = count_parallel(input, threads).unwrap_err();
+            assert_eq!(error.to_string(), "5:4: expected binary digit, found \"2\"");
+        }
+        let error = count_reader(input.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "5:4: expected binary digit, found \"2\"");
+
+        let input = "00100\n11110\n10110\n10111\n1011\n1é110\n";
+        for threads in [1, 3] {
+            let error = count_parallel(input, threads).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "5:1: expected 5 binary digits, found \"1011\""
+            );
+        }
+        let error = count_reader("1é110\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "1:2: expected binary digit, found \"é\"");
+    }
+}
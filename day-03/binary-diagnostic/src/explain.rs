@@ -0,0 +1,193 @@
+use crate::bits::BitVector;
+use crate::index::{Rating, RatingIndex, Step};
+use crate::TieBreak;
+use aoc_common::{Json, Result};
+use std::fmt;
+
+// Filtered column with the lines left after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExplainedStep {
+    pub step: Step,
+    pub remaining: usize,
+    // Listed only when few lines are left.
+    pub candidates: Option<Vec<BitVector>>,
+}
+
+// How the bit criteria found a rating, column by column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub rating: Rating,
+    pub tie_break: TieBreak,
+    pub lines: usize,
+    pub steps: Vec<ExplainedStep>,
+    pub survivor: Option<BitVector>,
+}
+
+impl Explanation {
+    // Record steps until the rating is found or the first error, which is
+    // returned together with the steps before it. Remaining lines are
+    // listed when there are at most `candidates` of them.
+    pub fn record(
+        index: &RatingIndex,
+        rating: Rating,
+        tie_break: TieBreak,
+        candidates: usize,
+    ) -> (Self, Result<BitVector>) {
+        let mut steps = Vec::new();
+        let result = index.walk(rating, tie_break, |step, walk| {
+            let remaining = walk.remaining();
+            steps.push(ExplainedStep {
+                step: *step,
+                remaining,
+                candidates: (remaining <= candidates).then(|| index.candidates(walk)),
+            });
+        });
+        let explanation = Explanation {
+            rating,
+            tie_break,
+            lines: index.root().remaining(),
+            steps,
+            survivor: result.as_ref().ok().cloned(),
+        };
+        (explanation, result)
+    }
+
+    pub fn step_json(&self, step: &ExplainedStep) -> Json {
+        let Step {
+            column,
+            ones,
+            zeros,
+            bit,
+        } = step.step;
+        let candidates = step.candidates.as_ref().map(|lines| {
+            lines
+                .iter()
+                .map(BitVector::to_binary)
+                .collect::<Vec<String>>()
+        });
+        Json::object()
+            .field("rating", self.rating.name())
+            .field("column", column + 1)
+            .field("ones", ones)
+            .field("zeros", zeros)
+            .field("kept", kept(bit))
+            .field("remaining", step.remaining)
+            .field("candidates", candidates)
+    }
+
+    pub fn survivor_json(&self) -> Json {
+        Json::object()
+            .field("rating", self.rating.name())
+            .field("survivor", self.survivor.as_ref().map(BitVector::to_binary))
+    }
+}
+
+fn kept(bit: Option<bool>) -> &'static str {
+    match bit {
+        Some(true) => "1",
+        Some(false) => "0",
+        None => "both",
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} (tie break {}), {} lines:",
+            self.rating.name(),
+            self.tie_break,
+            self.lines
+        )?;
+        for step in &self.steps {
+            let Step {
+                column,
+                ones,
+                zeros,
+                bit,
+            } = step.step;
+            writeln!(
+                f,
+                "  column {}: {} ones, {} zeros, keep {}, {} left",
+                column + 1,
+                ones,
+                zeros,
+                kept(bit),
+                step.remaining
+            )?;
+            for line in step.candidates.iter().flatten() {
+                writeln!(f, "    {}", line.to_binary())?;
+            }
+        }
+        match &self.survivor {
+            Some(line) => writeln!(f, "  survivor: {} ({})", line.to_binary(), line),
+            None => writeln!(f, "  no survivor"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BinaryDiagnostic;
+    use aoc_common::Solution;
+
+    #[test]
+    fn example_explanation() {
+        let input = std::fs::read_to_string("data/example.dat").unwrap();
+        let report = BinaryDiagnostic::parse(&input).unwrap();
+        let index = RatingIndex::new(&report);
+
+        let (explanation, result) =
+            Explanation::record(&index, Rating::OxygenGenerator, TieBreak::PreferOne, 2);
+        assert_eq!(result.unwrap().to_decimal(), "23");
+        assert_eq!(
+            explanation.to_string(),
+            "oxygen generator rating (tie break one), 12 lines:
+  column 1: 7 ones, 5 zeros, keep 1, 7 left
+  column 2: 3 ones, 4 zeros, keep 0, 4 left
+  column 3: 3 ones, 1 zeros, keep 1, 3 left
+  column 4: 2 ones, 1 zeros, keep 1, 2 left
+    10110
+    10111
+  column 5: 1 ones, 1 zeros, keep 1, 1 left
+    10111
+  survivor: 10111 (23)
+"
+        );
+
+        let (explanation, result) =
+            Explanation::record(&index, Rating::Co2Scrubber, TieBreak::PreferOne, 0);
+        assert_eq!(result.unwrap().to_decimal(), "10");
+        assert_eq!(explanation.steps.len(), 3);
+        assert_eq!(
+            explanation.step_json(&explanation.steps[2]).to_string(),
+            r#"{"rating":"CO2 scrubber rating","column":3,"ones":1,"zeros":1,"kept":"0","remaining":1,"candidates":null}"#
+        );
+        assert_eq!(
+            explanation.survivor_json().to_string(),
+            r#"{"rating":"CO2 scrubber rating","survivor":"01010"}"#
+        );
+    }
+
+    #[test]
+    fn explained_error() {
+        let report = BinaryDiagnostic::parse("10\n11\n00").unwrap();
+        let index = RatingIndex::new(&report);
+        let (explanation, result) =
+            Explanation::record(&index, Rating::OxygenGenerator, TieBreak::Error, 5);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "tie in column 2: 1 ones and 1 zeros"
+        );
+        assert_eq!(
+            explanation.to_string(),
+            "oxygen generator rating (tie break error), 3 lines:
+  column 1: 2 ones, 1 zeros, keep 1, 2 left
+    10
+    11
+  no survivor
+"
+        );
+    }
+}
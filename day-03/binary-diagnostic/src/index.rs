@@ -0,0 +1,282 @@
+use crate::bits::BitVector;
+use crate::{DiagnosticReport, TieBreak};
+use aoc_common::{Error, Result};
+use std::ops::Range;
+
+// Rating found by the bit criteria.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rating {
+    // Keeps lines with the most common bit.
+    OxygenGenerator,
+    // Keeps lines with the least common bit.
+    Co2Scrubber,
+}
+
+impl Rating {
+    pub fn name(self) -> &'static str {
+        match self {
+            Rating::OxygenGenerator => "oxygen generator rating",
+            Rating::Co2Scrubber => "CO2 scrubber rating",
+        }
+    }
+
+    // Bit kept in a column, `None` when both are kept.
+    pub fn criteria(
+        self,
+        tie_break: TieBreak,
+        ones: usize,
+        zeros: usize,
+        column: usize,
+    ) -> Result<Option<bool>> {
+        match self {
+            Rating::OxygenGenerator => tie_break.most_common(ones, zeros, column),
+            Rating::Co2Scrubber => tie_break.least_common(ones, zeros, column),
+        }
+    }
+}
+
+// Lines of the report sorted as numbers. Lines with a common prefix form a
+// contiguous range (a node of a binary trie with the length of the range as
+// its count), which the next column splits into zeros and ones by binary
+// search. A rating is a walk from the root, so both ratings need
+// O(n log n) to build the index and O(width log n) each.
+pub struct RatingIndex<'a> {
+    report: &'a DiagnosticReport,
+    lines: Vec<&'a [u64]>,
+}
+
+// State of a walk after some columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Walk {
+    // Nodes with the remaining lines. More than one only when the tie
+    // break keeps both bits.
+    pub nodes: Vec<Range<usize>>,
+    // Number of columns already filtered.
+    pub column: usize,
+}
+
+impl Walk {
+    pub fn remaining(&self) -> usize {
+        self.nodes.iter().map(|node| node.len()).sum()
+    }
+}
+
+// One filtered column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub column: usize,
+    pub ones: usize,
+    pub zeros: usize,
+    // `None` when both bits were kept.
+    pub bit: Option<bool>,
+}
+
+impl<'a> RatingIndex<'a> {
+    pub fn new(report: &'a DiagnosticReport) -> Self {
+        let mut lines = report.lines().collect::<Vec<&[u64]>>();
+        // Words are little-endian.
+        lines.sort_unstable_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+        RatingIndex { report, lines }
+    }
+
+    pub fn root(&self) -> Walk {
+        Walk {
+            nodes: vec![Range {
+                start: 0,
+                end: self.lines.len(),
+            }],
+            column: 0,
+        }
+    }
+
+    // Lines of the walk in ascending order.
+    pub fn lines(&self, walk: &Walk) -> impl Iterator<Item = &[u64]> + '_ {
+        let nodes = walk.nodes.clone();
+        nodes
+            .into_iter()
+            .flat_map(move |node| self.lines[node].iter().copied())
+    }
+
+    // Split every node of the walk by the next column and keep the children
+    // selected by the rating criteria. Returns `None` after the last column.
+    pub fn step(
+        &self,
+        walk: &mut Walk,
+        rating: Rating,
+        tie_break: TieBreak,
+    ) -> Result<Option<Step>> {
+        let column = walk.column;
+        if column >= self.report.width() {
+            return Ok(None);
+        }
+        let splits = walk
+            .nodes
+            .iter()
+            .map(|node| {
+                let lines = &self.lines[node.clone()];
+                node.start + lines.partition_point(|line| !self.report.bit(line, column))
+            })
+            .collect::<Vec<usize>>();
+        let zeros = walk
+            .nodes
+            .iter()
+            .zip(&splits)
+            .map(|(node, &split)| split - node.start)
+            .sum::<usize>();
+        let ones = walk.remaining() - zeros;
+        let bit = rating.criteria(tie_break, ones, zeros, column)?;
+
+        let mut nodes = Vec::with_capacity(walk.nodes.len());
+        for (node, split) in walk.nodes.iter().zip(splits) {
+            let children = [node.start..split, split..node.end];
+            match bit {
+                Some(bit) => nodes.push(children[usize::from(bit)].clone()),
+                None => nodes.extend(children),
+            }
+        }
+        nodes.retain(|node| !node.is_empty());
+        walk.nodes = nodes;
+        walk.column += 1;
+        Ok(Some(Step {
+            column,
+            ones,
+            zeros,
+            bit,
+        }))
+    }
+
+    // Filter columns until one line is left.
+    pub fn rating(&self, rating: Rating, tie_break: TieBreak) -> Result<BitVector> {
+        self.walk(rating, tie_break, |_, _| ())
+    }
+
+    // Like `rating`, `visit` is called after every column.
+    pub fn walk<F>(&self, rating: Rating, tie_break: TieBreak, mut visit: F) -> Result<BitVector>
+    where
+        F: FnMut(&Step, &Walk),
+    {
+        let mut walk = self.root();
+        while let Some(step) = self.step(&mut walk, rating, tie_break)? {
+            visit(&step, &walk);
+            if walk.remaining() == 1 {
+                break;
+            }
+        }
+        self.survivor(&walk)
+            .ok_or_else(|| Error::Solve(format!("unable to calculate {}", rating.name())))
+    }
+
+    // Remaining lines of the walk in ascending order.
+    pub fn candidates(&self, walk: &Walk) -> Vec<BitVector> {
+        self.lines(walk)
+            .map(|line| self.report.to_bit_vector(line))
+            .collect()
+    }
+
+    // The only remaining line of the walk.
+    pub fn survivor(&self, walk: &Walk) -> Option<BitVector> {
+        match walk.remaining() {
+            1 => self
+                .lines(walk)
+                .next()
+                .map(|line| self.report.to_bit_vector(line)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BinaryDiagnostic;
+    use aoc_common::Solution;
+
+    // Filtering by `retain`, as the ratings were computed before the index.
+    fn filter(report: &DiagnosticReport, rating: Rating, tie_break: TieBreak) -> Result<BitVector> {
+        let mut lines = report.lines().collect::<Vec<&[u64]>>();
+        for column in 0..report.width() {
+            let ones = report.ones_in_column(&lines, column);
+            let zeros = lines.len() - ones;
+            if let Some(bit) = rating.criteria(tie_break, ones, zeros, column)? {
+                lines.retain(|line| report.bit(line, column) == bit);
+            }
+            if lines.len() == 1 {
+                break;
+            }
+        }
+        match lines[..] {
+            [line] => Ok(report.to_bit_vector(line)),
+            _ => Err(Error::Solve(format!(
+                "unable to calculate {}",
+                rating.name()
+            ))),
+        }
+    }
+
+    #[test]
+    fn same_ratings_as_filtering() {
+        // Linear congruential generator, reports of all sizes with duplicate
+        // lines and ties.
+        let mut seed = 7u64;
+        let mut random = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        };
+        for case in 0..200 {
+            let width = 1 + case % 9;
+            let lines = (0..1 + random() % 40)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| if random() % 2 == 0 { '0' } else { '1' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>();
+            let report = BinaryDiagnostic::parse(&lines.join("\n")).unwrap();
+            let index = RatingIndex::new(&report);
+            for tie_break in TieBreak::ALL {
+                for rating in [Rating::OxygenGenerator, Rating::Co2Scrubber] {
+                    let expected = filter(&report, rating, tie_break).map_err(|e| e.to_string());
+                    let actual = index.rating(rating, tie_break).map_err(|e| e.to_string());
+                    assert_eq!(actual, expected, "{:?} {:?} {:?}", lines, rating, tie_break);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn walk() {
+        let report = BinaryDiagnostic::parse("10\n01\n11\n00\n11").unwrap();
+        let index = RatingIndex::new(&report);
+        let mut walk = index.root();
+        let lines = |walk: &Walk| {
+            index
+                .lines(walk)
+                .map(|line| index.report.to_bit_vector(line).to_binary())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(lines(&walk), ["00", "01", "10", "11", "11"]);
+
+        let step = index.step(&mut walk, Rating::OxygenGenerator, TieBreak::KeepBoth);
+        assert_eq!(
+            step.unwrap(),
+            Some(Step {
+                column: 0,
+                ones: 3,
+                zeros: 2,
+                bit: Some(true)
+            })
+        );
+        assert_eq!(lines(&walk), ["10", "11", "11"]);
+        let step = index.step(&mut walk, Rating::Co2Scrubber, TieBreak::KeepBoth);
+        assert_eq!(step.unwrap().map(|step| step.bit), Some(Some(false)));
+        assert_eq!(index.survivor(&walk).unwrap().to_binary(), "10");
+        assert_eq!(
+            index
+                .step(&mut walk, Rating::Co2Scrubber, TieBreak::KeepBoth)
+                .unwrap(),
+            None
+        );
+    }
+}
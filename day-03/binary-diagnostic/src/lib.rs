@@ -0,0 +1,474 @@
+use aoc_common::{Answer, Error, Result, Solution};
+use bits::{BitVector, Radix};
+use counting::ColumnCounts;
+use index::{Rating, RatingIndex};
+use std::fmt;
+use std::str::FromStr;
+
+pub mod bits;
+pub mod cli;
+pub mod counting;
+pub mod explain;
+pub mod index;
+
+#[derive(Debug)]
+pub struct FileStatistic {
+    pub lines: usize,
+    pub chars_in_line: usize,
+}
+
+// Diagnostic report. All lines have the same number of characters, every
+// line is packed into little-endian words (see `bits::position`) with the
+// first character as the most significant bit, so lines may be of any width.
+#[derive(Debug)]
+pub struct DiagnosticReport {
+    words: Vec<u64>,
+    words_per_line: usize,
+    pub statistic: FileStatistic,
+}
+
+impl DiagnosticReport {
+    pub fn width(&self) -> usize {
+        self.statistic.chars_in_line
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &[u64]> + '_ {
+        self.words.chunks_exact(self.words_per_line)
+    }
+
+    pub fn line(&self, index: usize) -> &[u64] {
+        let start = index * self.words_per_line;
+        &self.words[start..start + self.words_per_line]
+    }
+
+    pub fn bit(&self, line: &[u64], column: usize) -> bool {
+        let (word, mask) = bits::position(self.width(), column);
+        line[word] & mask != 0
+    }
+
+    pub fn to_bit_vector(&self, line: &[u64]) -> BitVector {
+        BitVector::from_words(self.width(), line)
+    }
+
+    // Number of `lines` with one in `column`.
+    pub fn ones_in_column(&self, lines: &[&[u64]], column: usize) -> usize {
+        let (word, mask) = bits::position(self.width(), column);
+        lines.iter().filter(|line| line[word] & mask != 0).count()
+    }
+
+    // Number of ones in every column of the report.
+    pub fn column_counts(&self) -> Vec<usize> {
+        let width = self.width();
+        let mut counts = vec![0; width];
+        for line in self.lines() {
+            for (i, &word) in line.iter().enumerate() {
+                // Only set bits are visited.
+                let mut bits = word;
+                while bits != 0 {
+                    let bit = i * u64::BITS as usize + bits.trailing_zeros() as usize;
+                    counts[width - 1 - bit] += 1;
+                    bits &= bits - 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+pub struct BinaryDiagnostic;
+
+impl Solution for BinaryDiagnostic {
+    type Input = DiagnosticReport;
+
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Binary Diagnostic";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines = input.lines().collect::<Vec<&str>>();
+        let statistic = make_file_statistic(&lines)?;
+        let width = statistic.chars_in_line;
+        let words_per_line = bits::words_for(width);
+        let mut words = vec![0; lines.len() * words_per_line];
+        for (i, (line, words)) in lines
+            .iter()
+            .zip(words.chunks_exact_mut(words_per_line))
+            .enumerate()
+        {
+            for (column, letter) in line.chars().enumerate() {
+                match letter {
+                    '0' => (),
+                    '1' => {
+                        let (word, mask) = bits::position(width, column);
+                        words[word] |= mask;
+                    }
+                    _ => {
+                        let found = letter.to_string();
+                        return Err(Error::parse(i + 1, column + 1, "binary digit", &found));
+                    }
+                }
+            }
+        }
+        Ok(DiagnosticReport {
+            words,
+            words_per_line,
+            statistic,
+        })
+    }
+
+    fn part_one(report: &Self::Input) -> Result<Answer> {
+        power_answer(report, TieBreak::default())
+    }
+
+    fn part_two(report: &Self::Input) -> Result<Answer> {
+        life_support_answer(report, TieBreak::default())
+    }
+}
+
+// What to do when a column has as many ones as zeros. The policy selects the
+// most common bit, the least common bit is its complement (so the puzzle's
+// rule is `PreferOne`: oxygen keeps ones and CO2 zeros).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    #[default]
+    PreferOne,
+    PreferZero,
+    Error,
+    // Ratings keep all lines, rates can't have both bits (error).
+    KeepBoth,
+}
+
+impl TieBreak {
+    pub const ALL: [TieBreak; 4] = [
+        TieBreak::PreferOne,
+        TieBreak::PreferZero,
+        TieBreak::Error,
+        TieBreak::KeepBoth,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TieBreak::PreferOne => "one",
+            TieBreak::PreferZero => "zero",
+            TieBreak::Error => "error",
+            TieBreak::KeepBoth => "both",
+        }
+    }
+
+    // Most common bit (`true` for one) of a column with `ones` and `zeros`,
+    // `None` when both bits are kept.
+    pub fn most_common(self, ones: usize, zeros: usize, column: usize) -> Result<Option<bool>> {
+        if ones != zeros {
+            return Ok(Some(ones > zeros));
+        }
+        match self {
+            TieBreak::PreferOne => Ok(Some(true)),
+            TieBreak::PreferZero => Ok(Some(false)),
+            TieBreak::KeepBoth => Ok(None),
+            TieBreak::Error => Err(Error::Solve(format!(
+                "tie in column {}: {} ones and {} zeros",
+                column + 1,
+                ones,
+                zeros
+            ))),
+        }
+    }
+
+    pub fn least_common(self, ones: usize, zeros: usize, column: usize) -> Result<Option<bool>> {
+        self.most_common(ones, zeros, column)
+            .map(|bit| bit.map(|bit| !bit))
+    }
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for TieBreak {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        TieBreak::ALL
+            .into_iter()
+            .find(|tie_break| tie_break.name() == name)
+            .ok_or_else(|| {
+                let names = TieBreak::ALL.map(TieBreak::name);
+                Error::Usage(format!(
+                    "unknown tie break: '{}', expected one of: {}",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+pub fn power_answer(report: &DiagnosticReport, tie_break: TieBreak) -> Result<Answer> {
+    counts_answer(&ColumnCounts::from_report(report), tie_break)
+}
+
+// Like `power_answer`, but from counts of the report.
+pub fn counts_answer(counts: &ColumnCounts, tie_break: TieBreak) -> Result<Answer> {
+    let (gamma_rate, epsilon_rate) = rates(counts, tie_break)?;
+    let product = gamma_rate.mul(&epsilon_rate);
+    Ok(Answer::from(&product)
+        .with("gamma_rate", &gamma_rate)
+        .with("epsilon_rate", &epsilon_rate)
+        .with("gamma_rate_bits", gamma_rate.to_binary())
+        .with("epsilon_rate_bits", epsilon_rate.to_binary())
+        .with("product_bits", product.to_binary())
+        .with("tie_break", tie_break.to_string()))
+}
+
+pub fn life_support_answer(report: &DiagnosticReport, tie_break: TieBreak) -> Result<Answer> {
+    let (oxygen_generator_rating, co2_scrubber_rating) = life_support_ratings(report, tie_break)?;
+    Ok(ratings_answer(
+        &oxygen_generator_rating,
+        &co2_scrubber_rating,
+        tie_break,
+    ))
+}
+
+// Like `life_support_answer`, but from ratings found elsewhere.
+pub fn ratings_answer(
+    oxygen_generator_rating: &BitVector,
+    co2_scrubber_rating: &BitVector,
+    tie_break: TieBreak,
+) -> Answer {
+    let product = oxygen_generator_rating.mul(co2_scrubber_rating);
+    Answer::from(&product)
+        .with("oxygen_generator_rating", oxygen_generator_rating)
+        .with("co2_scrubber_rating", co2_scrubber_rating)
+        .with(
+            "oxygen_generator_rating_bits",
+            oxygen_generator_rating.to_binary(),
+        )
+        .with("co2_scrubber_rating_bits", co2_scrubber_rating.to_binary())
+        .with("product_bits", product.to_binary())
+        .with("tie_break", tie_break.to_string())
+}
+
+// Detail `name` of the answer in `radix`, `{name}_bits` detail holds the
+// binary digits.
+pub fn format_detail(answer: &Answer, name: &str, radix: Radix) -> String {
+    let digits = answer
+        .detail(&format!("{}_bits", name))
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    match BitVector::parse(&digits) {
+        Some(vector) => radix.format(&vector),
+        None => answer[name].to_string(),
+    }
+}
+
+// Returns `(gamma rate, epsilon rate)`.
+pub fn power_consumption(
+    report: &DiagnosticReport,
+    tie_break: TieBreak,
+) -> Result<(BitVector, BitVector)> {
+    rates(&ColumnCounts::from_report(report), tie_break)
+}
+
+// Returns `(gamma rate, epsilon rate)` of counted report.
+pub fn rates(counts: &ColumnCounts, tie_break: TieBreak) -> Result<(BitVector, BitVector)> {
+    let mut gamma_rate = BitVector::zero(counts.width());
+    for (column, &ones) in counts.ones.iter().enumerate() {
+        match tie_break.most_common(ones, counts.lines - ones, column)? {
+            Some(true) => gamma_rate.set(column),
+            Some(false) => (),
+            None => {
+                return Err(Error::Solve(format!(
+                    "tie in column {}: gamma rate can't keep both bits",
+                    column + 1
+                )))
+            }
+        }
+    }
+    let epsilon_rate = gamma_rate.not();
+    Ok((gamma_rate, epsilon_rate))
+}
+
+// Returns `(oxygen generator rating, CO2 scrubber rating)`.
+pub fn life_support_ratings(
+    report: &DiagnosticReport,
+    tie_break: TieBreak,
+) -> Result<(BitVector, BitVector)> {
+    let index = RatingIndex::new(report);
+    let oxygen_generator_rating = index.rating(Rating::OxygenGenerator, tie_break)?;
+    let co2_scrubber_rating = index.rating(Rating::Co2Scrubber, tie_break)?;
+    Ok((oxygen_generator_rating, co2_scrubber_rating))
+}
+
+pub fn oxygen_generator_rating(
+    report: &DiagnosticReport,
+    tie_break: TieBreak,
+) -> Result<BitVector> {
+    RatingIndex::new(report).rating(Rating::OxygenGenerator, tie_break)
+}
+
+pub fn co2_scrubber_rating(report: &DiagnosticReport, tie_break: TieBreak) -> Result<BitVector> {
+    RatingIndex::new(report).rating(Rating::Co2Scrubber, tie_break)
+}
+
+pub fn make_file_statistic<T>(lines: &[T]) -> Result<FileStatistic>
+where
+    T: AsRef<str>,
+{
+    let mut statistic = FileStatistic {
+        lines: 0,
+        chars_in_line: 0,
+    };
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        if i == 0 {
+            statistic.chars_in_line = line.chars().count();
+        }
+        // Check that all lines in the file have the same number of characters.
+        if line.chars().count() != statistic.chars_in_line {
+            let expected = format!("{} binary digits", statistic.chars_in_line);
+            return Err(Error::parse(i + 1, 1, &expected, line));
+        }
+        statistic.lines = i + 1;
+    }
+    Ok(statistic)
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::*;
+
+    fn report() -> DiagnosticReport {
+        let input = std::fs::read_to_string("data/example.dat").unwrap();
+        BinaryDiagnostic::parse(&input).unwrap()
+    }
+
+    fn values((a, b): (BitVector, BitVector)) -> (String, String) {
+        (a.to_decimal(), b.to_decimal())
+    }
+
+    #[test]
+    fn power_consumption_example() {
+        assert_eq!(
+            values(power_consumption(&report(), TieBreak::PreferOne).unwrap()),
+            ("22".into(), "9".into())
+        );
+    }
+
+    #[test]
+    fn life_support_ratings_example() {
+        let ratings = life_support_ratings(&report(), TieBreak::PreferOne).unwrap();
+        assert_eq!(values(ratings), ("23".into(), "10".into()));
+    }
+
+    #[test]
+    fn packed_columns() {
+        let report = report();
+        assert_eq!(report.line(1), [0b11110]);
+        assert_eq!(report.lines().count(), 12);
+        assert_eq!(report.column_counts(), vec![7, 5, 8, 7, 5]);
+        let lines = report.lines().take(3).collect::<Vec<&[u64]>>();
+        assert_eq!(report.ones_in_column(&lines, 1), 1);
+    }
+
+    #[test]
+    fn wide_report() {
+        // Columns 0 and 100 have ones in two of three lines.
+        let line = |ones: &[usize]| {
+            (0..130)
+                .map(|column| if ones.contains(&column) { '1' } else { '0' })
+                .collect::<String>()
+        };
+        let input = [line(&[0, 100]), line(&[0, 129]), line(&[100])].join("\n");
+        let report = BinaryDiagnostic::parse(&input).unwrap();
+        assert_eq!(report.line(0).len(), 3);
+        let counts = report.column_counts();
+        assert_eq!((counts[0], counts[100], counts[129]), (2, 2, 1));
+
+        let answer = BinaryDiagnostic::part_one(&report).unwrap();
+        assert_eq!(answer["gamma_rate_bits"].to_string(), line(&[0, 100]));
+        let gamma = BitVector::parse(&line(&[0, 100])).unwrap();
+        let product = gamma.mul(&gamma.not());
+        assert_eq!(answer.to_string(), product.to_decimal());
+        assert!(product.to_u128().is_none());
+        assert_eq!(
+            format_detail(&answer, "gamma_rate", Radix::Hex),
+            format!("2{}2{}", "0".repeat(24), "0".repeat(7))
+        );
+
+        let answer = BinaryDiagnostic::part_two(&report).unwrap();
+        assert_eq!(
+            answer["oxygen_generator_rating_bits"].to_string(),
+            line(&[0, 100])
+        );
+        assert_eq!(answer["co2_scrubber_rating_bits"].to_string(), line(&[100]));
+    }
+
+    #[test]
+    fn example_radixes() {
+        let answer = BinaryDiagnostic::part_one(&report()).unwrap();
+        assert_eq!(format_detail(&answer, "gamma_rate", Radix::Binary), "10110");
+        assert_eq!(format_detail(&answer, "epsilon_rate", Radix::Hex), "09");
+        assert_eq!(format_detail(&answer, "product", Radix::Decimal), "198");
+        assert_eq!(answer.to_string(), "198");
+    }
+
+    #[test]
+    fn tie_columns() {
+        // Columns 1, 2 and 4 are tied in the whole report.
+        let report = BinaryDiagnostic::parse("1100\n1011\n0010\n0111").unwrap();
+        let rates = |tie_break| {
+            power_consumption(&report, tie_break)
+                .map(|(gamma, epsilon)| (gamma.to_binary(), epsilon.to_binary()))
+                .map_err(|e| e.to_string())
+        };
+        let ratings = |tie_break| {
+            life_support_ratings(&report, tie_break)
+                .map(|(oxygen, co2)| (oxygen.to_binary(), co2.to_binary()))
+                .map_err(|e| e.to_string())
+        };
+        let pair = |a: &str, b: &str| Ok((a.to_owned(), b.to_owned()));
+
+        assert_eq!(rates(TieBreak::PreferOne), pair("1111", "0000"));
+        assert_eq!(rates(TieBreak::PreferZero), pair("0010", "1101"));
+        assert_eq!(
+            rates(TieBreak::Error),
+            Err("tie in column 1: 2 ones and 2 zeros".into())
+        );
+        assert_eq!(
+            rates(TieBreak::KeepBoth),
+            Err("tie in column 1: gamma rate can't keep both bits".into())
+        );
+
+        assert_eq!(ratings(TieBreak::PreferOne), pair("1100", "0010"));
+        assert_eq!(ratings(TieBreak::PreferZero), pair("0010", "1100"));
+        assert_eq!(
+            ratings(TieBreak::Error),
+            Err("tie in column 1: 2 ones and 2 zeros".into())
+        );
+        // Oxygen keeps all lines in columns 1 and 2, 1011 and 0111 are left
+        // after the last column. CO2 keeps the only zero of column 3.
+        assert_eq!(
+            ratings(TieBreak::KeepBoth),
+            Err("unable to calculate oxygen generator rating".into())
+        );
+        let co2 = co2_scrubber_rating(&report, TieBreak::KeepBoth).unwrap();
+        assert_eq!(co2.to_binary(), "1100");
+
+        let answer = power_answer(&report, TieBreak::PreferZero).unwrap();
+        assert_eq!(answer["tie_break"].to_string(), "zero");
+        assert_eq!("both".parse::<TieBreak>().unwrap(), TieBreak::KeepBoth);
+        assert!("never".parse::<TieBreak>().is_err());
+    }
+
+    #[test]
+    fn malformed_report() {
+        let error = BinaryDiagnostic::parse("00100\n11110\n10120").unwrap_err();
+        assert_eq!(error.to_string(), "3:4: expected binary digit, found \"2\"");
+
+        let error = BinaryDiagnostic::parse("00100\n1111").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: expected 5 binary digits, found \"1111\""
+        );
+    }
+}
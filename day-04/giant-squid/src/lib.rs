@@ -0,0 +1,270 @@
+use aoc_common::{parse_integer, Answer, Error, Result, Solution};
+
+// Board has fixed dimensions.
+// It contains 5 rows and 5 columns (25 numbers).
+#[derive(Clone, Debug)]
+pub struct Board {
+    // TODO: Use two dimensional array instead of Vec?
+    cells: Vec<Cell>,
+    is_winning: bool,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board {
+            cells: Vec::with_capacity(25),
+            is_winning: false,
+        }
+    }
+}
+
+impl Board {
+    fn mark(&mut self, number: usize) {
+        for cell in &mut self.cells {
+            if cell.number == number {
+                cell.is_marked = true;
+            }
+        }
+    }
+
+    fn is_all_numbers_in_row_marked(&self) -> bool {
+        for row in self.cells.chunks_exact(5) {
+            if row.iter().all(|c| c.is_marked) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn is_all_numbers_in_column_marked(&self) -> bool {
+        // TODO: Use two dimensional array and transpose it?
+        for column_idx in 0..5 {
+            let column = self
+                .cells
+                .iter()
+                .skip(column_idx)
+                .step_by(5)
+                .collect::<Vec<_>>();
+
+            if column.iter().all(|c| c.is_marked) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn sum_of_all_unmarked_numbers(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|c| !c.is_marked)
+            .map(|c| c.number)
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Cell {
+    number: usize,
+    is_marked: bool,
+}
+
+impl Cell {
+    pub fn new(number: usize) -> Self {
+        Cell {
+            number,
+            is_marked: false,
+        }
+    }
+}
+
+pub struct WinBoard {
+    // Position of the board in the input (counted from 0).
+    pub index: usize,
+    pub board: Board,
+    pub last_called_number: usize,
+}
+
+impl WinBoard {
+    pub fn final_score(&self) -> usize {
+        self.board.sum_of_all_unmarked_numbers() * self.last_called_number
+    }
+
+    fn answer(&self) -> Answer {
+        Answer::from(self.final_score())
+            .with("board_index", self.index)
+            .with("last_called_number", self.last_called_number)
+            .with(
+                "sum_of_unmarked_numbers",
+                self.board.sum_of_all_unmarked_numbers(),
+            )
+    }
+}
+
+// Input numbers and boards.
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub numbers: Vec<usize>,
+    pub boards: Vec<Board>,
+}
+
+pub struct GiantSquid;
+
+impl Solution for GiantSquid {
+    type Input = Game;
+
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Giant Squid";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        // Keep line numbers for error messages.
+        let lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        // Input numbers are saved as first line in file.
+        let (line_number, first_line) = lines
+            .first()
+            .ok_or_else(|| Error::parse(1, 1, "input numbers", ""))?;
+        let numbers = parse_numbers(first_line, *line_number)?;
+
+        // One board has 5 rows and 5 columns (25 numbers).
+        let rows = &lines[1..];
+        if rows.len() % 5 != 0 {
+            let (line_number, line) = rows[rows.len() - rows.len() % 5];
+            return Err(Error::parse(line_number, 1, "board with 5 rows", line));
+        }
+        let boards = rows
+            .chunks_exact(5)
+            .map(parse_board)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Game { numbers, boards })
+    }
+
+    fn part_one(game: &Self::Input) -> Result<Answer> {
+        let mut boards = game.boards.clone();
+        let win_board = first_winning_board(&game.numbers, &mut boards)
+            .ok_or_else(|| Error::solve("win board not found"))?;
+        Ok(win_board.answer())
+    }
+
+    fn part_two(game: &Self::Input) -> Result<Answer> {
+        let mut boards = game.boards.clone();
+        let win_board = last_winning_board(&game.numbers, &mut boards)
+            .ok_or_else(|| Error::solve("win board not found"))?;
+        Ok(win_board.answer())
+    }
+}
+
+// Parse comma-separated input numbers.
+fn parse_numbers(line: &str, line_number: usize) -> Result<Vec<usize>> {
+    line.split(',')
+        .map(|item| parse_integer(line, item.trim(), line_number))
+        .collect()
+}
+
+// Parse board rows given with their line numbers. Every row has to contain
+// exactly 5 numbers.
+fn parse_board(rows: &[(usize, &str)]) -> Result<Board> {
+    let mut cells = Vec::with_capacity(25);
+    for (line_number, row) in rows {
+        let numbers = row
+            .split_ascii_whitespace()
+            .map(|item| parse_integer(row, item, *line_number))
+            .collect::<Result<Vec<usize>>>()?;
+        if numbers.len() != 5 {
+            return Err(Error::parse(*line_number, 1, "5 numbers in row", row));
+        }
+        cells.extend(numbers.into_iter().map(Cell::new));
+    }
+    Ok(Board {
+        cells,
+        is_winning: false,
+    })
+}
+
+// Returns first winning board as `Some(..)` when found win row or column.
+// Otherwise returns `None`.
+pub fn first_winning_board(numbers: &[usize], boards: &mut [Board]) -> Option<WinBoard> {
+    for number in numbers {
+        for (index, board) in boards.iter_mut().enumerate() {
+            board.mark(*number);
+
+            if board.is_all_numbers_in_row_marked() || board.is_all_numbers_in_column_marked() {
+                return Some(WinBoard {
+                    index,
+                    board: board.clone(),
+                    last_called_number: *number,
+                });
+            }
+        }
+    }
+    None
+}
+
+// Returns last winning board as `Some(..)` when found win row or column.
+// Otherwise returns `None`.
+pub fn last_winning_board(numbers: &[usize], boards: &mut [Board]) -> Option<WinBoard> {
+    let mut last_winning_board = None;
+
+    for number in numbers {
+        for (index, board) in boards.iter_mut().enumerate() {
+            board.mark(*number);
+
+            if !board.is_winning
+                && (board.is_all_numbers_in_row_marked() || board.is_all_numbers_in_column_marked())
+            {
+                board.is_winning = true;
+                last_winning_board = Some(WinBoard {
+                    index,
+                    board: board.clone(),
+                    last_called_number: *number,
+                });
+            }
+        }
+    }
+    last_winning_board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::Value;
+
+    #[test]
+    fn input_numbers() {
+        assert_eq!(parse_numbers("1,2, 3,4", 1).unwrap(), vec![1, 2, 3, 4]);
+
+        let error = parse_numbers("1,2,aa,bb,,3,#,4", 1).unwrap_err();
+        assert_eq!(error.to_string(), "1:5: expected integer, found \"aa\"");
+
+        let rows = [(2, "1 2 aa 3   4")];
+        let error = parse_board(&rows).unwrap_err();
+        assert_eq!(error.to_string(), "2:5: expected integer, found \"aa\"");
+    }
+
+    #[test]
+    fn example_data() {
+        let input = std::fs::read_to_string("data/example.dat").unwrap();
+        let game = GiantSquid::parse(&input).unwrap();
+        assert_eq!(
+            GiantSquid::part_one(&game).unwrap().value,
+            Value::Integer(4512)
+        );
+        assert_eq!(
+            GiantSquid::part_two(&game).unwrap().value,
+            Value::Integer(1924)
+        );
+    }
+
+    #[test]
+    fn malformed_board() {
+        let input = std::fs::read_to_string("data/example.dat").unwrap();
+        let input = input.replacen("21  9 14 16  7", "21  9 1a 16  7", 1);
+        let error = GiantSquid::parse(&input).unwrap_err();
+        assert_eq!(error.to_string(), "5:7: expected integer, found \"1a\"");
+    }
+}
@@ -0,0 +1,190 @@
+use aoc_common::{column_of, parse_integer, Answer, Error, Result, Solution};
+
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+// Parse point (slice of `line`).
+fn parse_point(line: &str, raw_str: &str, line_number: usize) -> Result<Point> {
+    // Example input: `123,456`.
+    let splitted_str = raw_str.split_terminator(',').collect::<Vec<_>>();
+
+    if splitted_str.len() != 2 {
+        let column = column_of(line, raw_str);
+        return Err(Error::parse(line_number, column, "point 'x,y'", raw_str));
+    }
+
+    let x = parse_coordinate(line, splitted_str[0], line_number, CORR_X_MAX)?;
+    let y = parse_coordinate(line, splitted_str[1], line_number, CORR_Y_MAX)?;
+
+    Ok(Point { x, y })
+}
+
+// Parse coordinate (slice of `line`), which has to fit on the venture map.
+fn parse_coordinate(line: &str, token: &str, line_number: usize, max: usize) -> Result<isize> {
+    let value = parse_integer::<isize>(line, token, line_number)?;
+    if value < 0 || value as usize >= max {
+        let expected = format!("coordinate in 0..{}", max);
+        return Err(Error::parse(
+            line_number,
+            column_of(line, token),
+            &expected,
+            token,
+        ));
+    }
+    Ok(value)
+}
+
+pub struct HydrothermalVenture;
+
+impl Solution for HydrothermalVenture {
+    type Input = Vec<(Point, Point)>;
+
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Hydrothermal Venture";
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_line_into_points(line, i + 1))
+            .collect()
+    }
+
+    fn part_one(lines: &Self::Input) -> Result<Answer> {
+        // Main assume: input contains only horizontal and vertical lines.
+        // Other lines are skipped.
+        let lines = lines
+            .iter()
+            .filter(|(p1, p2)| p1.x == p2.x || p1.y == p2.y)
+            .cloned()
+            .collect();
+        Ok(count_overlaps(lines).into())
+    }
+
+    fn part_two(lines: &Self::Input) -> Result<Answer> {
+        Ok(count_overlaps(lines.clone()).into())
+    }
+}
+
+const CORR_X_MAX: usize = 1000;
+const CORR_Y_MAX: usize = 1000;
+
+// Returns number of points where at least two lines overlap.
+pub fn count_overlaps(lines: Vec<(Point, Point)>) -> usize {
+    // Fixed venture map. 1000 by 1000 elements.
+    // Parser checks that coordinates are lower than 1000.
+    let mut venture_map = vec![0; CORR_X_MAX * CORR_Y_MAX];
+
+    for (p1, p2) in lines {
+        let points = make_points(p1, p2);
+
+        for p in points {
+            venture_map[p.x as usize + p.y as usize * CORR_Y_MAX] += 1;
+        }
+    }
+
+    venture_map.into_iter().filter(|i| *i >= 2).count()
+}
+
+pub fn parse_line_into_points(line: &str, line_number: usize) -> Result<(Point, Point)> {
+    // Example input: `123,456 -> 589,012`
+    let splitted_line = line.split(" -> ").collect::<Vec<_>>();
+    if splitted_line.len() != 2 {
+        return Err(Error::parse(line_number, 1, "'x1,y1 -> x2,y2'", line));
+    }
+    let first_point = parse_point(line, splitted_line[0], line_number)?;
+    let second_point = parse_point(line, splitted_line[1], line_number)?;
+
+    // `make_points` draws only these lines.
+    let x_distance = (second_point.x - first_point.x).abs();
+    let y_distance = (second_point.y - first_point.y).abs();
+    if x_distance != 0 && y_distance != 0 && x_distance != y_distance {
+        return Err(Error::parse(
+            line_number,
+            1,
+            "horizontal, vertical or 45° diagonal line",
+            line,
+        ));
+    }
+
+    Ok((first_point, second_point))
+}
+
+pub fn make_points(p1: Point, p2: Point) -> Vec<Point> {
+    let x_distance = p2.x - p1.x;
+    let y_distance = p2.y - p1.y;
+    let number_of_new_points = if x_distance == 0 {
+        // Vertical line.
+        y_distance.abs()
+    } else {
+        // Horizontal line or diagonal line.
+        // For diagonal line `x_distance == y_distance`.
+        x_distance.abs()
+    };
+
+    (0..=number_of_new_points)
+        .map(|n| Point {
+            x: p1.x + n * x_distance.signum(),
+            y: p1.y + n * y_distance.signum(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::*;
+    use aoc_common::Value;
+
+    #[test]
+    fn example_data() {
+        let input = std::fs::read_to_string("data/example.dat").unwrap();
+        let lines = HydrothermalVenture::parse(&input).unwrap();
+        assert_eq!(
+            HydrothermalVenture::part_one(&lines).unwrap().value,
+            Value::Integer(5)
+        );
+        assert_eq!(
+            HydrothermalVenture::part_two(&lines).unwrap().value,
+            Value::Integer(12)
+        );
+    }
+
+    #[test]
+    fn malformed_line() {
+        let error = HydrothermalVenture::parse("0,9 -> 5,9\n8,0 -> 0;8").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:8: expected point 'x,y', found \"0;8\""
+        );
+    }
+
+    #[test]
+    fn coordinates_out_of_map() {
+        let error = |input| HydrothermalVenture::parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error("0,0 -> 0,1500"),
+            "1:10: expected coordinate in 0..1000, found \"1500\""
+        );
+        assert_eq!(
+            error("-1,0 -> 3,0"),
+            "1:1: expected coordinate in 0..1000, found \"-1\""
+        );
+        assert_eq!(
+            error("0,5 -> 1000,5"),
+            "1:8: expected coordinate in 0..1000, found \"1000\""
+        );
+        assert!(HydrothermalVenture::parse("0,0 -> 999,999").is_ok());
+    }
+
+    #[test]
+    fn skewed_line() {
+        let error = HydrothermalVenture::parse("0,0 -> 8,8\n1,1 -> 3,2").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: expected horizontal, vertical or 45° diagonal line, found \"1,1 -> 3,2\""
+        );
+    }
+}